@@ -0,0 +1,231 @@
+use crate::{Bytes,Error,ErrorKind};
+
+/// Which bit of a byte is read or written first
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum BitOrder {
+    /// From the most significant bit down, like GIF frames and H.264.
+    MsbFirst,
+    /// From the least significant bit up, like DEFLATE.
+    LsbFirst,
+}
+
+/// Reads bits from a byte view
+///
+/// Bytes are taken from the view as soon as any of their bits are read. Errors count positions and
+/// lengths in bits from where the `BitView` started.
+pub struct BitView<V> {
+    view: V,
+    order: BitOrder,
+    byte: u8,
+    left: u32,
+    pos: usize,
+}
+
+/// Bit reading over the bytes of a view without taking them
+struct Cursor<'a> {
+    buf: &'a [u8],
+    order: BitOrder,
+    next: usize,
+    byte: u8,
+    left: u32,
+}
+
+impl Cursor<'_> {
+    fn bit(&mut self) -> Option<u64> {
+        if self.left == 0 {
+            self.byte = *self.buf.get(self.next)?;
+            self.next += 1;
+            self.left = 8;
+        }
+        let index = match self.order {
+            BitOrder::MsbFirst => self.left - 1,
+            BitOrder::LsbFirst => 8 - self.left,
+        };
+        self.left -= 1;
+        Some(u64::from(self.byte >> index & 1))
+    }
+
+    fn bits(&mut self, n: u32) -> Option<u64> {
+        let mut out = 0;
+        for i in 0..n {
+            let bit = self.bit()?;
+            match self.order {
+                BitOrder::MsbFirst => out = out << 1 | bit,
+                BitOrder::LsbFirst => out |= bit << i,
+            }
+        }
+        Some(out)
+    }
+}
+
+impl<V: Bytes> BitView<V> {
+    pub fn new(view: V, order: BitOrder) -> BitView<V> {
+        BitView { view, order, byte: 0, left: 0, pos: 0 }
+    }
+
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// Bits read so far
+    pub fn bit_pos(&self) -> usize {
+        self.pos
+    }
+
+    /// Whether the next bit starts a byte
+    pub fn is_aligned(&self) -> bool {
+        self.left == 0
+    }
+
+    /// Skip the rest of the current byte
+    pub fn align_to_byte(&mut self) {
+        self.pos += self.left as usize;
+        self.left = 0;
+    }
+
+    /// Take `n` bits, at most 64. With `LsbFirst` the first bit read is the least significant.
+    pub fn take_bits(&mut self, n: u32) -> Result<u64, Error> {
+        self.read(n, |cursor| cursor.bits(n))
+    }
+
+    /// Like [`take_bits`](#method.take_bits) without moving the view.
+    pub fn peek_bits(&self, n: u32) -> Result<u64, Error> {
+        if n > 64 { return Err(Error::overflow(self.pos)) }
+        let mut cursor = self.cursor();
+        cursor.bits(n).ok_or_else(|| self.out_of_bounds(n as usize))
+    }
+
+    pub fn take_bit(&mut self) -> Result<bool, Error> {
+        Ok(self.take_bits(1)? == 1)
+    }
+
+    /// Take an unsigned exp-Golomb code, `ue(v)` in H.264
+    pub fn take_ue(&mut self) -> Result<u64, Error> {
+        let pos = self.pos;
+        let mut zeros = 0;
+        let out = self.read(0, |cursor| {
+            while cursor.bit()? == 0 {
+                zeros += 1;
+                if zeros > 63 { return None }
+            }
+            Some((1 << zeros) - 1 + cursor.bits(zeros)?)
+        });
+        match out {
+            Err(_) if zeros > 63 => Err(Error::overflow(pos)),
+            Err(_) => Err(self.out_of_bounds(2 * zeros as usize + 1)),
+            out => out,
+        }
+    }
+
+    /// Take a signed exp-Golomb code, `se(v)` in H.264
+    pub fn take_se(&mut self) -> Result<i64, Error> {
+        let k = self.take_ue()?;
+        let magnitude = (k / 2 + k % 2) as i64;
+        Ok(if k % 2 == 1 { magnitude } else { -magnitude })
+    }
+
+    /// Stop reading bits. The view is after the last byte any bits were read from.
+    pub fn into_inner(self) -> V {
+        self.view
+    }
+
+    fn cursor(&self) -> Cursor<'_> {
+        Cursor {
+            buf: self.view.as_ref(),
+            order: self.order,
+            next: 0,
+            byte: self.byte,
+            left: self.left,
+        }
+    }
+
+    fn out_of_bounds(&self, n: usize) -> Error {
+        let available = self.left as usize + 8 * self.view.as_ref().len();
+        Error::new(ErrorKind::OutOfBits, self.pos, n, available)
+    }
+
+    /// Runs `f` over the upcoming bits and takes what it read if it succeeds
+    fn read<F>(&mut self, n: u32, f: F) -> Result<u64, Error>
+    where
+        F: FnOnce(&mut Cursor) -> Option<u64>
+    {
+        if n > 64 { return Err(Error::overflow(self.pos)) }
+        let mut cursor = self.cursor();
+        let out = match f(&mut cursor) {
+            Some(out) => out,
+            None => return Err(self.out_of_bounds(n as usize)),
+        };
+        let (next, byte, left) = (cursor.next, cursor.byte, cursor.left);
+        self.pos += 8 * next + self.left as usize - left as usize;
+        self.view.take(next)?;
+        self.byte = byte;
+        self.left = left;
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    #[test]
+    fn test_take_bits_msb() {
+        let mut a = BitView::new(&[0b1010_0110,0b1100_0011][..], BitOrder::MsbFirst);
+
+        assert!(a.take_bit() == Ok(true));
+        assert!(a.take_bits(3) == Ok(0b010));
+        assert!(a.peek_bits(8) == Ok(0b0110_1100));
+        assert!(a.take_bits(8) == Ok(0b0110_1100));
+        assert!(a.bit_pos() == 12);
+        assert!(a.take_bits(5) == Err(Error::new(ErrorKind::OutOfBits, 12, 5, 4)));
+        assert!(a.take_bits(4) == Ok(0b0011));
+        assert!(a.take_bit().is_err());
+    }
+
+    #[test]
+    fn test_take_bits_lsb() {
+        let mut a = BitView::new(&[0b1010_0110,0b1100_0011][..], BitOrder::LsbFirst);
+
+        assert!(a.take_bit() == Ok(false));
+        assert!(a.take_bits(3) == Ok(0b011));
+        assert!(a.take_bits(8) == Ok(0b0011_1010));
+        assert!(a.take_bits(4) == Ok(0b1100));
+        assert!(a.take_bits(65).unwrap_err().kind == ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_align() {
+        let mut a = BitView::new(&[0xFF,0x12,0x34][..], BitOrder::MsbFirst);
+
+        assert!(a.is_aligned());
+        assert!(a.take_bits(3) == Ok(0b111));
+        assert!(!a.is_aligned());
+        a.align_to_byte();
+        assert!(a.is_aligned());
+        assert!(a.bit_pos() == 8);
+        assert!(a.take_bits(8) == Ok(0x12));
+        assert!(a.take_bits(4) == Ok(0x3));
+
+        let rest = a.into_inner();
+        assert!(rest.is_empty(), "took the partly read byte");
+    }
+
+    #[test]
+    fn test_exp_golomb() {
+        // 1, 010, 011, 00100, 00101, 0000000 (too short)
+        let mut a = BitView::new(&[0b1010_0110,0b0100_0010,0b1000_0000,0][..], BitOrder::MsbFirst);
+
+        assert!(a.take_ue() == Ok(0));
+        assert!(a.take_ue() == Ok(1));
+        assert!(a.take_ue() == Ok(2));
+        assert!(a.take_se() == Ok(2));
+        assert!(a.take_se() == Ok(-2));
+        let pos = a.bit_pos();
+        assert!(a.take_ue().is_err());
+        assert!(a.bit_pos() == pos, "took none");
+
+        let mut a = BitView::new(&[0; 9][..], BitOrder::MsbFirst);
+        assert!(a.take_ue().unwrap_err().kind == ErrorKind::Overflow);
+    }
+}
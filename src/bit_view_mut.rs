@@ -0,0 +1,166 @@
+use crate::{BitOrder,BytesMut,Error,ErrorKind};
+
+/// Writes bits into a byte view
+///
+/// Bits are collected until they fill a byte, which is then put into the view. Call
+/// [`pad_to_byte`](#method.pad_to_byte) or [`finish`](#method.finish) to write a partly filled
+/// byte. Errors count positions and lengths in bits from where the `BitViewMut` started.
+pub struct BitViewMut<V> {
+    view: V,
+    order: BitOrder,
+    byte: u8,
+    filled: u32,
+    pos: usize,
+}
+
+impl<V: BytesMut> BitViewMut<V> {
+    pub fn new(view: V, order: BitOrder) -> BitViewMut<V> {
+        BitViewMut { view, order, byte: 0, filled: 0, pos: 0 }
+    }
+
+    pub fn order(&self) -> BitOrder {
+        self.order
+    }
+
+    /// Bits written so far, including ones not yet put into the view
+    pub fn bits_written(&self) -> usize {
+        self.pos
+    }
+
+    /// Whether the next bit starts a byte
+    pub fn is_aligned(&self) -> bool {
+        self.filled == 0
+    }
+
+    /// Put the low `n` bits of `val`, at most 64. With `LsbFirst` the least significant bit is
+    /// written first.
+    ///
+    /// Nothing is written if the bytes they complete don't fit in the view. Bits of a partly filled
+    /// byte don't need room until it's complete.
+    pub fn put_bits(&mut self, val: u64, n: u32) -> Result<(), Error> {
+        if n > 64 { return Err(Error::overflow(self.pos)) }
+        let mut out = [0u8; 9];
+        let mut len = 0;
+        let mut byte = self.byte;
+        let mut filled = self.filled;
+        for i in 0..n {
+            let bit = match self.order {
+                BitOrder::MsbFirst => val >> (n - 1 - i) & 1,
+                BitOrder::LsbFirst => val >> i & 1,
+            } as u8;
+            byte |= match self.order {
+                BitOrder::MsbFirst => bit << (7 - filled),
+                BitOrder::LsbFirst => bit << filled,
+            };
+            filled += 1;
+            if filled == 8 {
+                out[len] = byte;
+                len += 1;
+                byte = 0;
+                filled = 0;
+            }
+        }
+        self.view.put(&out[..len]).map_err(|e| match e.kind {
+            ErrorKind::OutOfBounds => {
+                let available = 8 * e.available + 7 - self.filled as usize;
+                Error::new(ErrorKind::OutOfBits, self.pos, n as usize, available)
+            }
+            _ => e,
+        })?;
+        self.byte = byte;
+        self.filled = filled;
+        self.pos += n as usize;
+        Ok(())
+    }
+
+    pub fn put_bit(&mut self, bit: bool) -> Result<(), Error> {
+        self.put_bits(u64::from(bit), 1)
+    }
+
+    /// Fill the rest of the current byte with `fill` bits and put it into the view
+    pub fn pad_to_byte(&mut self, fill: bool) -> Result<(), Error> {
+        if self.filled == 0 { return Ok(()) }
+        let n = 8 - self.filled;
+        let val = if fill { u64::MAX >> (64 - n) } else { 0 };
+        self.put_bits(val, n)
+    }
+
+    /// Pad the current byte with `fill` bits and give back the view after it
+    pub fn finish(mut self, fill: bool) -> Result<V, Error> {
+        self.pad_to_byte(fill)?;
+        Ok(self.view)
+    }
+
+    /// Give back the view, dropping bits of a partly filled byte
+    pub fn into_inner(self) -> V {
+        self.view
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BitView;
+
+    #[test]
+    fn test_put_bits_msb() {
+        let mut src = [0u8; 2];
+        let mut a = BitViewMut::new(&mut src[..], BitOrder::MsbFirst);
+
+        assert!(a.put_bit(true).is_ok());
+        assert!(a.put_bits(0b010, 3).is_ok());
+        assert!(a.put_bits(0b0110_1100, 8).is_ok());
+        assert!(a.bits_written() == 12);
+        assert!(a.put_bits(0, 12) == Err(Error::new(ErrorKind::OutOfBits, 12, 12, 11)));
+        assert!(a.put_bits(0b0011, 4).is_ok());
+        assert!(a.put_bit(true).is_ok(), "pending");
+        assert!(a.pad_to_byte(false).is_err());
+        assert!(src == [0b1010_0110,0b1100_0011]);
+    }
+
+    #[test]
+    fn test_put_bits_lsb() {
+        let mut src = [0u8; 2];
+        let mut a = BitViewMut::new(&mut src[..], BitOrder::LsbFirst);
+
+        assert!(a.put_bit(false).is_ok());
+        assert!(a.put_bits(0b011, 3).is_ok());
+        assert!(a.put_bits(0b0011_1010, 8).is_ok());
+        assert!(a.put_bits(0b1100, 4).is_ok());
+        assert!(a.put_bits(0, 65).unwrap_err().kind == ErrorKind::Overflow);
+        assert!(src == [0b1010_0110,0b1100_0011]);
+    }
+
+    #[test]
+    fn test_pad() {
+        let mut src = [0u8; 3];
+        let mut a = BitViewMut::new(&mut src[..], BitOrder::MsbFirst);
+
+        assert!(a.pad_to_byte(true).is_ok(), "aligned already");
+        assert!(a.put_bits(0b101, 3).is_ok());
+        assert!(!a.is_aligned());
+        assert!(a.pad_to_byte(true).is_ok());
+        assert!(a.is_aligned());
+        assert!(a.bits_written() == 8);
+        assert!(a.put_bits(0b1, 1).is_ok());
+
+        let rest = a.finish(false).unwrap();
+        assert!(rest.len() == 1);
+        assert!(src == [0b1011_1111,0b1000_0000,0]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut src = [0u8; 4];
+        let mut a = BitViewMut::new(&mut src[..], BitOrder::LsbFirst);
+        for n in 1..8 {
+            assert!(a.put_bits(n as u64, n).is_ok());
+        }
+        assert!(a.finish(false).is_ok());
+
+        let mut b = BitView::new(&src[..], BitOrder::LsbFirst);
+        for n in 1..8 {
+            assert!(b.take_bits(n) == Ok(n as u64));
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{BytesMut,Error,Patch,Pos,Put,ViewMut};
+
+/// A byte view that only counts what's put into it, to measure an encoding before writing it
+///
+/// Puts always succeed unless the encoding itself fails, e.g. a prefix that overflows. Nothing can
+/// be written in place, so [`take_slice_mut`](./trait.ViewMut.html#method.take_slice_mut) is
+/// out of bounds. The position is the count.
+#[derive(Debug,Clone,Copy,Default,PartialEq,Eq)]
+pub struct Counter {
+    count: usize,
+}
+
+impl Counter {
+    pub fn new() -> Counter {
+        Counter { count: 0 }
+    }
+
+    /// Bytes put so far
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Count what `f` puts
+    pub fn measure<F>(f: F) -> Result<usize, Error>
+    where
+        F: FnOnce(&mut Counter) -> Result<(), Error>
+    {
+        let mut counter = Counter::new();
+        f(&mut counter)?;
+        Ok(counter.count)
+    }
+
+    fn add(&mut self, n: usize) -> Result<(), Error> {
+        self.count = self.count.checked_add(n).ok_or_else(|| Error::overflow(self.count))?;
+        Ok(())
+    }
+}
+
+/// Always empty
+impl AsMut<[u8]> for Counter {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut []
+    }
+}
+
+impl Pos for Counter {
+    fn pos(&self) -> usize {
+        self.count
+    }
+}
+
+impl ViewMut<u8> for Counter {
+    fn put(&mut self, val: &[u8]) -> Result<(), Error> {
+        self.add(val.len())
+    }
+
+    fn room(&mut self) -> usize {
+        usize::MAX - self.count
+    }
+
+    fn reserve(&mut self, _n: usize) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl BytesMut for Counter {}
+
+/// Reserved fields are only counted
+impl Patch for Counter {
+    type Mark = ();
+
+    fn reserve_bytes(&mut self, n: usize) -> Result<(), Error> {
+        self.add(n)
+    }
+
+    fn fill_bytes(&mut self, _mark: (), _val: &[u8]) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// How many bytes a value puts, e.g. to allocate a buffer of the right size before writing it
+///
+/// Anything that can be [`Put`](./trait.Put.html) into a [`Counter`](./struct.Counter.html) has
+/// it, including types that derive `Put`.
+pub trait EncodedLen {
+    fn encoded_len(&self) -> Result<usize, Error>;
+}
+
+impl<T: Put<Counter> + ?Sized> EncodedLen for T {
+    fn encoded_len(&self) -> Result<usize, Error> {
+        Counter::measure(|counter| self.put_into(counter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Be,ErrorKind,U32Le};
+
+    #[test]
+    fn test_count() {
+        let mut a = Counter::new();
+
+        assert!(a.put_u32_le(1).is_ok());
+        assert!(a.put_prefixed::<u16, Be>(b"abc").is_ok());
+        assert!(a.put_uleb128(300).is_ok());
+        assert!(a.put_fixed_str("x", 8, 0).is_ok());
+        assert!(a.put_length_prefixed::<u8, Be, _>(|w| w.put_str_nul("hi")).is_ok());
+        assert!(a.count() == 4 + 5 + 2 + 8 + 4);
+        assert!(a.take_mut::<u8>().unwrap_err().kind == ErrorKind::OutOfBounds);
+        assert!(a.put_fixed_str("toolong", 2, 0).is_err(), "encoding errors still happen");
+    }
+
+    #[test]
+    fn test_encoded_len() {
+        let header = (U32Le::new(1), [0u8; 3]);
+        assert!(header.0.encoded_len() == Ok(4));
+        assert!(header.1.encoded_len() == Ok(3));
+
+        let len = Counter::measure(|w| {
+            header.0.put_into(w)?;
+            header.1.put_into(w)
+        });
+        assert!(len == Ok(7));
+
+        let mut buf = [0u8; 7];
+        let mut b = &mut buf[..];
+        assert!(header.0.put_into(&mut b).is_ok());
+        assert!(header.1.put_into(&mut b).is_ok());
+        assert!(b.is_empty());
+    }
+}
@@ -0,0 +1,127 @@
+use core::fmt;
+
+use crate::Pod;
+
+macro_rules! endian_ints {
+    ($($name:ident, $typ:tt, $endian:ident, $doc:expr;)*) => {
+        $(
+            #[doc = concat!("A `", stringify!($typ), "` stored as ", $doc, " endian bytes")]
+            ///
+            /// It has an alignment of 1 so it can be a field of a `#[repr(C)]` struct cast with
+            /// [`take_ref`](./trait.Bytes.html#method.take_ref) at any position.
+            #[derive(Clone,Copy,PartialEq,Eq,Hash,Default)]
+            #[repr(transparent)]
+            pub struct $name([u8; core::mem::size_of::<$typ>()]);
+
+            impl $name {
+                pub fn new(val: $typ) -> $name {
+                    $name(endian_ints!(@to $endian, $typ, val))
+                }
+
+                pub fn get(self) -> $typ {
+                    endian_ints!(@from $endian, $typ, self.0)
+                }
+
+                pub fn set(&mut self, val: $typ) {
+                    *self = $name::new(val);
+                }
+            }
+
+            impl From<$typ> for $name {
+                fn from(val: $typ) -> $name {
+                    $name::new(val)
+                }
+            }
+
+            impl From<$name> for $typ {
+                fn from(val: $name) -> $typ {
+                    val.get()
+                }
+            }
+
+            impl fmt::Debug for $name {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    fmt::Debug::fmt(&self.get(), f)
+                }
+            }
+
+            unsafe impl Pod for $name {}
+        )*
+    };
+    (@to le, $typ:tt, $val:expr) => { $typ::to_le_bytes($val) };
+    (@to be, $typ:tt, $val:expr) => { $typ::to_be_bytes($val) };
+    (@from le, $typ:tt, $val:expr) => { $typ::from_le_bytes($val) };
+    (@from be, $typ:tt, $val:expr) => { $typ::from_be_bytes($val) };
+}
+
+endian_ints! {
+    U16Le, u16, le, "little";
+    U16Be, u16, be, "big";
+    I16Le, i16, le, "little";
+    I16Be, i16, be, "big";
+    U32Le, u32, le, "little";
+    U32Be, u32, be, "big";
+    I32Le, i32, le, "little";
+    I32Be, i32, be, "big";
+    U64Le, u64, le, "little";
+    U64Be, u64, be, "big";
+    I64Le, i64, le, "little";
+    I64Be, i64, be, "big";
+    U128Le, u128, le, "little";
+    U128Be, u128, be, "big";
+    I128Le, i128, le, "little";
+    I128Be, i128, be, "big";
+    F32Le, f32, le, "little";
+    F32Be, f32, be, "big";
+    F64Le, f64, le, "little";
+    F64Be, f64, be, "big";
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Bytes,BytesMut};
+
+    #[derive(Clone,Copy,PartialEq,Debug)]
+    #[repr(C)]
+    struct Header {
+        magic: [u8; 2],
+        len: U32Le,
+        crc: U16Be,
+    }
+    unsafe impl Pod for Header {}
+
+    #[test]
+    fn test_get_set() {
+        let mut a = U32Be::new(1);
+        assert!(a.get() == 1);
+        a.set(0x01020304);
+        assert!(u32::from(a) == 0x01020304);
+        assert!(a == U32Be::from(0x01020304));
+        assert!(core::mem::align_of::<F64Le>() == 1);
+        assert!(F32Be::new(1.5).get() == 1.5);
+    }
+
+    #[test]
+    fn test_take_put() {
+        let src = [b'H',b'D',5,0,0,0,0x12,0x34,0xFF];
+
+        let mut a = &src[..];
+        let header = *a.take_ref::<Header>().unwrap();
+        assert!(header.len.get() == 5);
+        assert!(header.crc.get() == 0x1234);
+        assert!(a == [0xFF]);
+
+        let mut a = &src[..];
+        assert!(a.take_pod::<Header>() == Ok(header));
+
+        let mut dst = [0u8; 9];
+        let mut b = &mut dst[..];
+        let out = b.take_mut::<Header>().unwrap();
+        *out = header;
+        out.len.set(6);
+        assert!(b.put_pod(&U16Le::new(0xFF)).is_err());
+        assert!(b.put_pod(&0xFFu8).is_ok());
+        assert!(dst == [b'H',b'D',6,0,0,0,0x12,0x34,0xFF]);
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{Bytes,BytesMut,Endianness,Error,FromBytes,ToBytes};
+
+/// A byte view with a byte order chosen at runtime
+///
+/// Formats like TIFF and ELF declare their byte order in a header. Wrap the view once the order is
+/// known and use `take_u32` instead of branching between `take_u32_le` and `take_u32_be`.
+pub struct EndianView<V> {
+    view: V,
+    endianness: Endianness,
+}
+
+impl<V> EndianView<V> {
+    pub fn new(view: V, endianness: Endianness) -> EndianView<V> {
+        EndianView { view, endianness }
+    }
+
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    pub fn inner(&self) -> &V {
+        &self.view
+    }
+
+    /// The wrapped view, for taking or putting bytes that don't have a byte order
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.view
+    }
+
+    pub fn into_inner(self) -> V {
+        self.view
+    }
+}
+
+impl<V: Bytes> EndianView<V> {
+    pub fn take_num<N: FromBytes>(&mut self) -> Result<N, Error> {
+        let mut bytes = N::Bytes::default();
+        let n = bytes.as_ref().len();
+        bytes.as_mut().copy_from_slice(self.view.take(n)?);
+        Ok(self.endianness.from_bytes(bytes))
+    }
+
+    pub fn peek_num<N: FromBytes>(&self) -> Result<N, Error> {
+        let mut bytes = N::Bytes::default();
+        let n = bytes.as_ref().len();
+        bytes.as_mut().copy_from_slice(self.view.peek(n)?);
+        Ok(self.endianness.from_bytes(bytes))
+    }
+
+    pub fn take_u8(&mut self) -> Result<u8, Error> {
+        self.view.take_u8()
+    }
+
+    pub fn take_i8(&mut self) -> Result<i8, Error> {
+        self.view.take_i8()
+    }
+
+    pub fn take_u16(&mut self) -> Result<u16, Error> {
+        self.take_num::<u16>()
+    }
+
+    pub fn take_i16(&mut self) -> Result<i16, Error> {
+        self.take_num::<i16>()
+    }
+
+    pub fn take_u32(&mut self) -> Result<u32, Error> {
+        self.take_num::<u32>()
+    }
+
+    pub fn take_i32(&mut self) -> Result<i32, Error> {
+        self.take_num::<i32>()
+    }
+
+    pub fn take_u64(&mut self) -> Result<u64, Error> {
+        self.take_num::<u64>()
+    }
+
+    pub fn take_i64(&mut self) -> Result<i64, Error> {
+        self.take_num::<i64>()
+    }
+
+    pub fn take_u128(&mut self) -> Result<u128, Error> {
+        self.take_num::<u128>()
+    }
+
+    pub fn take_i128(&mut self) -> Result<i128, Error> {
+        self.take_num::<i128>()
+    }
+
+    pub fn take_f32(&mut self) -> Result<f32, Error> {
+        self.take_num::<f32>()
+    }
+
+    pub fn take_f64(&mut self) -> Result<f64, Error> {
+        self.take_num::<f64>()
+    }
+}
+
+impl<V: BytesMut> EndianView<V> {
+    pub fn put_num<N: ToBytes>(&mut self, val: N) -> Result<(), Error> {
+        self.view.put(self.endianness.to_bytes(val).as_ref())
+    }
+
+    pub fn put_u8(&mut self, val: u8) -> Result<(), Error> {
+        self.view.put_u8(val)
+    }
+
+    pub fn put_i8(&mut self, val: i8) -> Result<(), Error> {
+        self.view.put_i8(val)
+    }
+
+    pub fn put_u16(&mut self, val: u16) -> Result<(), Error> {
+        self.put_num::<u16>(val)
+    }
+
+    pub fn put_i16(&mut self, val: i16) -> Result<(), Error> {
+        self.put_num::<i16>(val)
+    }
+
+    pub fn put_u32(&mut self, val: u32) -> Result<(), Error> {
+        self.put_num::<u32>(val)
+    }
+
+    pub fn put_i32(&mut self, val: i32) -> Result<(), Error> {
+        self.put_num::<i32>(val)
+    }
+
+    pub fn put_u64(&mut self, val: u64) -> Result<(), Error> {
+        self.put_num::<u64>(val)
+    }
+
+    pub fn put_i64(&mut self, val: i64) -> Result<(), Error> {
+        self.put_num::<i64>(val)
+    }
+
+    pub fn put_u128(&mut self, val: u128) -> Result<(), Error> {
+        self.put_num::<u128>(val)
+    }
+
+    pub fn put_i128(&mut self, val: i128) -> Result<(), Error> {
+        self.put_num::<i128>(val)
+    }
+
+    pub fn put_f32(&mut self, val: f32) -> Result<(), Error> {
+        self.put_num::<f32>(val)
+    }
+
+    pub fn put_f64(&mut self, val: f64) -> Result<(), Error> {
+        self.put_num::<f64>(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Look,View};
+
+    #[test]
+    fn test_take() {
+        let src = &[b'M',b'M',0,42,0,0,0,8][..];
+        let mut look = Look::new(src);
+        let endianness = match look.take(2) {
+            Ok(b"II") => Endianness::Little,
+            Ok(b"MM") => Endianness::Big,
+            _ => panic!("bad header"),
+        };
+        let mut a = EndianView::new(look, endianness);
+
+        assert!(a.peek_num::<u16>() == Ok(42));
+        assert!(a.take_u16() == Ok(42));
+        assert!(a.take_u32() == Ok(8));
+        assert!(a.take_u8().is_err());
+
+        let mut a = EndianView::new(&[42,0][..], Endianness::Little);
+        assert!(a.take_i16() == Ok(42));
+    }
+
+    #[test]
+    fn test_put() {
+        let mut src = [0u8; 6];
+        let mut a = EndianView::new(&mut src[..], Endianness::Big);
+
+        assert!(a.put_u16(1).is_ok());
+        a.set_endianness(Endianness::Little);
+        assert!(a.put_u32(2).is_ok());
+        assert!(a.put_u8(3).is_err());
+        assert!(src == [0,1,2,0,0,0]);
+    }
+
+    #[test]
+    fn test_native() {
+        let src = 1u32.to_ne_bytes();
+        let mut a = EndianView::new(&src[..], Endianness::NATIVE);
+        assert!(a.take_u32() == Ok(1));
+    }
+}
@@ -0,0 +1,183 @@
+use core::fmt;
+use core::str;
+
+/// What kind of bad position an [`Error`](./struct.Error.html) is
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Not enough elements left in the view
+    OutOfBounds,
+    /// Not enough bits left in a [`BitView`](./struct.BitView.html) or
+    /// [`BitViewMut`](./struct.BitViewMut.html). The position and lengths count bits.
+    OutOfBits,
+    /// The bytes aren't valid UTF8. The first `valid_up_to` bytes are.
+    InvalidUtf8 { valid_up_to: usize },
+    /// No NUL was found before the end of the view
+    MissingTerminator,
+    /// A number doesn't fit in its type, e.g. a position past `usize::MAX` or a LEB128 number
+    /// too big for a `u32`
+    Overflow,
+    /// A variable length number uses more bytes than its type can need
+    Overlong,
+    /// A length is over the limit given by the caller
+    TooLong { max: usize },
+    /// The data isn't aligned for the type it's cast to
+    Misaligned { align: usize },
+    /// An enum tag doesn't match any variant
+    UnknownTag,
+    /// The elements span more than one chunk of a [`Segments`](./struct.Segments.html) view, or
+    /// the end of a [`Ring`](./struct.Ring.html)'s storage
+    NotContiguous,
+    /// The input ended early but more of it could succeed. At least `needed` more elements of the
+    /// underlying view are missing, e.g. bytes. See
+    /// [`Error::streaming`](./struct.Error.html#method.streaming).
+    Incomplete { needed: usize },
+    /// Elements were left over, e.g. in a chunk read with
+    /// [`take_view`](./trait.View.html#method.take_view)
+    Trailing,
+    /// A `Display` or other formatting implementation failed
+    Format,
+    /// A slice doesn't have the length that is written before it, e.g. by a derived `Put`
+    LengthMismatch,
+    /// A NUL-terminated string has a NUL before its end, so it would be read back shorter
+    InteriorNul,
+}
+
+/// When an operation goes to a bad position. E.g. out of bounds or invalid UTF8
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    /// Position of the view when the operation failed. See [`Pos`](./trait.Pos.html).
+    pub pos: usize,
+    /// Elements the operation needed
+    pub requested: usize,
+    /// Elements that were left in the view
+    pub available: usize,
+}
+
+/// The old name of [`Error`](./struct.Error.html)
+#[deprecated(note = "renamed to `Error`")]
+pub type BadPos = Error;
+
+impl Error {
+    pub fn new(kind: ErrorKind, pos: usize, requested: usize, available: usize) -> Error {
+        Error { kind, pos, requested, available }
+    }
+
+    pub fn out_of_bounds(pos: usize, requested: usize, available: usize) -> Error {
+        Error::new(ErrorKind::OutOfBounds, pos, requested, available)
+    }
+
+    pub fn overflow(pos: usize) -> Error {
+        Error::new(ErrorKind::Overflow, pos, 0, 0)
+    }
+
+    /// Treat running out of input as [`Incomplete`](./enum.ErrorKind.html#variant.Incomplete),
+    /// for parsing data that is still arriving. Other errors stay as they are.
+    ///
+    /// `needed` counts elements of the view that ran out. Running out of bits counts the bytes
+    /// that hold them instead, so it's the same unit as the bytes under a
+    /// [`BitView`](./struct.BitView.html).
+    pub fn streaming(self) -> Error {
+        let missing = self.requested.saturating_sub(self.available);
+        let needed = match self.kind {
+            ErrorKind::OutOfBounds | ErrorKind::MissingTerminator => missing,
+            ErrorKind::OutOfBits => missing.div_ceil(8),
+            _ => return self,
+        };
+        Error { kind: ErrorKind::Incomplete { needed: needed.max(1) }, ..self }
+    }
+
+    /// Whether more input could make the operation succeed
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self.kind, ErrorKind::Incomplete { .. })
+    }
+
+    /// Moves an error from a view starting at 0 to one starting at `pos`
+    pub(crate) fn offset(mut self, pos: usize) -> Error {
+        self.pos += pos;
+        self
+    }
+
+    pub(crate) fn check(pos: usize, requested: usize, available: usize) -> Result<(), Error> {
+        if requested > available {
+            Err(Error::out_of_bounds(pos, requested, available))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub(crate) fn from_utf8(pos: usize, bytes: &[u8]) -> Result<&str, Error> {
+        str::from_utf8(bytes).map_err(|e| {
+            let kind = ErrorKind::InvalidUtf8 { valid_up_to: e.valid_up_to() };
+            Error::new(kind, pos, bytes.len(), bytes.len())
+        })
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ErrorKind::OutOfBounds => write!(
+                f, "out of bounds at {}: requested {} but {} available",
+                self.pos, self.requested, self.available
+            ),
+            ErrorKind::OutOfBits => write!(
+                f, "out of bits at bit {}: requested {} bits but {} available",
+                self.pos, self.requested, self.available
+            ),
+            ErrorKind::InvalidUtf8 { valid_up_to } => write!(
+                f, "invalid utf8 at {}: valid up to {}",
+                self.pos, valid_up_to
+            ),
+            ErrorKind::MissingTerminator => write!(
+                f, "missing nul terminator at {}: none in {} available",
+                self.pos, self.available
+            ),
+            ErrorKind::Overflow => write!(f, "overflow at {}", self.pos),
+            ErrorKind::Overlong => write!(f, "overlong number at {}", self.pos),
+            ErrorKind::TooLong { max } => write!(
+                f, "too long at {}: {} is over the limit of {}",
+                self.pos, self.requested, max
+            ),
+            ErrorKind::Misaligned { align } => write!(
+                f, "misaligned at {}: needs an alignment of {}",
+                self.pos, align
+            ),
+            ErrorKind::UnknownTag => write!(f, "unknown tag at {}", self.pos),
+            ErrorKind::NotContiguous => write!(
+                f, "not contiguous at {}: {} requested across chunks",
+                self.pos, self.requested
+            ),
+            ErrorKind::Incomplete { needed } => write!(
+                f, "incomplete at {}: needs {} more",
+                self.pos, needed
+            ),
+            ErrorKind::Trailing => write!(
+                f, "trailing data at {}: {} left",
+                self.pos, self.available
+            ),
+            ErrorKind::Format => write!(f, "formatting failed at {}", self.pos),
+            ErrorKind::LengthMismatch => write!(
+                f, "length mismatch at {}: {} elements but a length of {}",
+                self.pos, self.requested, self.available
+            ),
+            ErrorKind::InteriorNul => write!(f, "nul inside a nul-terminated string at {}", self.pos),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Position of a view in its buffer, which is reported in errors
+///
+/// Slices don't know where they started so they are always at 0.
+pub trait Pos {
+    fn pos(&self) -> usize {
+        0
+    }
+}
+
+impl<T> Pos for &[T] {}
+impl<T> Pos for &mut [T] {}
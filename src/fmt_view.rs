@@ -0,0 +1,145 @@
+use core::fmt;
+
+use crate::{Error,ErrorKind,ViewMut};
+
+/// Formats into a byte view with `core::fmt::Write`, e.g. `write!(FmtView::new(&mut buf), "{}", x)`
+///
+/// By default a string that doesn't fit fails the write and isn't put at all. The error is kept
+/// in [`error`](#method.error) since `fmt::Error` has no details. A
+/// [`truncating`](#method.truncating) view instead puts what fits, cut at a UTF-8 boundary, and
+/// drops everything after it, and [`format`](#method.format) says how much was put. See also
+/// [`put_fmt`](./trait.BytesMut.html#method.put_fmt).
+pub struct FmtView<'a, V: ?Sized> {
+    view: &'a mut V,
+    written: usize,
+    truncate: bool,
+    truncated: bool,
+    error: Option<Error>,
+}
+
+impl<'a, V: ViewMut<u8> + ?Sized> FmtView<'a, V> {
+    pub fn new(view: &'a mut V) -> FmtView<'a, V> {
+        FmtView { view, written: 0, truncate: false, truncated: false, error: None }
+    }
+
+    pub fn truncating(view: &'a mut V) -> FmtView<'a, V> {
+        FmtView { truncate: true, ..FmtView::new(view) }
+    }
+
+    /// Bytes formatted into the view so far
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Whether a truncating view ran out of room
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Why the last write failed, if the view failed it
+    pub fn error(&self) -> Option<Error> {
+        self.error
+    }
+
+    /// Write `args` and return how many bytes were put, which for a truncating view can be fewer
+    /// than formatted. Fails with the view's error or
+    /// [`Format`](./enum.ErrorKind.html#variant.Format).
+    pub fn format(&mut self, args: fmt::Arguments) -> Result<usize, Error> {
+        let written = self.written;
+        fmt::write(self, args).map_err(|_| match self.error {
+            Some(e) => e,
+            None => Error::new(ErrorKind::Format, self.view.pos(), 0, 0),
+        })?;
+        Ok(self.written - written)
+    }
+}
+
+impl<V: ViewMut<u8> + ?Sized> fmt::Write for FmtView<'_, V> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.truncated { return Ok(()) }
+        let mut n = s.len();
+        if let Err(e) = self.view.reserve(n) {
+            if !self.truncate {
+                self.error = Some(e);
+                return Err(fmt::Error)
+            }
+            n = n.min(self.view.room());
+            while !s.is_char_boundary(n) { n -= 1 }
+            self.truncated = true;
+        }
+        if let Err(e) = self.view.put(&s.as_bytes()[..n]) {
+            self.error = Some(e);
+            return Err(fmt::Error)
+        }
+        self.written += n;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+    use crate::{BytesMut,Limit,Look,Ring,View,ViewMut};
+
+    #[test]
+    fn test_write() {
+        let mut buf = [0u8; 8];
+        let mut a = &mut buf[..];
+
+        let mut w = FmtView::new(&mut a);
+        assert!(write!(w, "t={}", 21).is_ok());
+        assert!(w.written() == 4);
+        assert!(write!(w, "{:>4}", 'x').is_ok());
+        assert!(write!(w, "!").is_err());
+        assert!(w.error() == Some(Error::out_of_bounds(0, 1, 0)));
+        assert!(a.is_empty());
+        assert!(buf == *b"t=21   x");
+    }
+
+    #[test]
+    fn test_truncating() {
+        let mut buf = [0u8; 5];
+        let mut a = Look::new(&mut buf[..]);
+
+        let mut w = FmtView::truncating(&mut a);
+        assert!(write!(w, "ab{}é", 12).is_ok());
+        assert!(w.is_truncated());
+        assert!(w.written() == 4, "é isn't split");
+        assert!(write!(w, "{}", 3).is_ok());
+        assert!(a.pos() == 4);
+        assert!(buf[..4] == *b"ab12");
+
+        let mut b = Ring::<u8, 6>::new();
+        assert!(b.put(&[0; 4]).is_ok());
+        assert!(b.take(3).is_ok());
+        assert!(FmtView::truncating(&mut b).format(format_args!("{}", 123_456)) == Ok(5));
+        assert!(b.as_slices() == (&[0,b'1',b'2'][..], &b"345"[..]), "space that wraps");
+
+        let mut buf = [0u8; 8];
+        let mut c = &mut buf[..];
+        let mut limit = Limit::new(&mut c, 3);
+        assert!(FmtView::truncating(&mut limit).format(format_args!("{}", 12_345)) == Ok(3));
+        drop(limit);
+        assert!(buf[..4] == *b"123\0");
+    }
+
+    #[test]
+    fn test_put_fmt() {
+        let mut buf = [0u8; 8];
+        let mut a = Look::new(&mut buf[..]);
+
+        assert!(a.put_fmt(format_args!("{}-{}", 1, 23)) == Ok(4));
+        let e = a.put_fmt(format_args!("{}{}", 1234, 5)).unwrap_err();
+        assert!(e == Error::out_of_bounds(4, 5, 4));
+        assert!(a.pos() == 4, "put nothing");
+
+        struct Fails;
+        impl fmt::Display for Fails {
+            fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+                Err(fmt::Error)
+            }
+        }
+        assert!(a.put_fmt(format_args!("{}", Fails)).unwrap_err().kind == ErrorKind::Format);
+    }
+}
@@ -0,0 +1,223 @@
+use core::convert::TryFrom;
+use std::io::{self,Read};
+use std::vec::Vec;
+
+use crate::{Bytes,Error,ErrorKind,Look,Pos,SeekFrom,View,ViewMut};
+
+/// Running out of elements or bits and incomplete errors are `UnexpectedEof`, and the rest are
+/// `InvalidData`
+impl From<Error> for io::Error {
+    fn from(e: Error) -> io::Error {
+        let kind = match e.kind {
+            ErrorKind::OutOfBounds | ErrorKind::OutOfBits | ErrorKind::Incomplete { .. } => {
+                io::ErrorKind::UnexpectedEof
+            }
+            _ => io::ErrorKind::InvalidData,
+        };
+        io::Error::new(kind, e)
+    }
+}
+
+/// Reads from a byte view with `io::Read` and `io::BufRead`, or writes into one with `io::Write`
+pub struct IoView<V> {
+    view: V,
+}
+
+impl<V> IoView<V> {
+    pub fn new(view: V) -> IoView<V> {
+        IoView { view }
+    }
+
+    pub fn inner(&self) -> &V {
+        &self.view
+    }
+
+    pub fn inner_mut(&mut self) -> &mut V {
+        &mut self.view
+    }
+
+    pub fn into_inner(self) -> V {
+        self.view
+    }
+}
+
+impl<V: View<u8>> io::Read for IoView<V> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = buf.len().min(self.view.as_ref().len());
+        buf[..n].copy_from_slice(self.view.take(n)?);
+        Ok(n)
+    }
+}
+
+impl<V: View<u8>> io::BufRead for IoView<V> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.view.as_ref())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        let amt = amt.min(self.view.as_ref().len());
+        let _ = self.view.take(amt);
+    }
+}
+
+/// Writes as much as fits. Views that grow, like [`VecView`](./struct.VecView.html), take all of it.
+impl<V: ViewMut<u8>> io::Write for IoView<V> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = match self.view.reserve(buf.len()) {
+            Ok(()) => buf.len(),
+            Err(_) => buf.len().min(self.view.as_mut().len()),
+        };
+        self.view.put(&buf[..n])?;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Seeking outside the buffer is `InvalidInput`, unlike files which can seek past the end.
+impl<B: AsRef<[u8]>> io::Seek for Look<u8, B> {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let invalid = |_| io::Error::from(io::ErrorKind::InvalidInput);
+        let from = match pos {
+            io::SeekFrom::Start(pos) => SeekFrom::Start(usize::try_from(pos).map_err(invalid)?),
+            io::SeekFrom::Current(offset) => SeekFrom::Current(isize::try_from(offset).map_err(invalid)?),
+            io::SeekFrom::End(offset) => SeekFrom::End(isize::try_from(offset).map_err(invalid)?),
+        };
+        let pos = Look::seek(self, from).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        Ok(pos as u64)
+    }
+}
+
+/// A byte view over fixed-size records read from an `io::BufRead`
+///
+/// [`next_record`](#method.next_record) reads a record into a buffer that the
+/// [`Bytes`](./trait.Bytes.html) methods then take from. The position counts bytes from the start
+/// of the reader.
+pub struct ReadView<R> {
+    reader: R,
+    buf: Vec<u8>,
+    start: usize,
+    offset: usize,
+}
+
+impl<R: io::BufRead> ReadView<R> {
+    pub fn new(reader: R) -> ReadView<R> {
+        ReadView { reader, buf: Vec::new(), start: 0, offset: 0 }
+    }
+
+    /// Read the next `size` bytes, dropping what's left of the last record. Returns `false` if the
+    /// reader ended before the record, or `UnexpectedEof` if it ended inside it. If it fails
+    /// inside a record the view is empty, and the position still counts the bytes that were read.
+    pub fn next_record(&mut self, size: usize) -> io::Result<bool> {
+        self.offset += self.buf.len();
+        self.buf.clear();
+        self.start = 0;
+        if self.reader.fill_buf()?.is_empty() { return Ok(false) }
+        let read = self.reader.by_ref().take(size as u64).read_to_end(&mut self.buf);
+        let e = match read {
+            Ok(n) if n == size => return Ok(true),
+            Ok(_) => io::Error::from(io::ErrorKind::UnexpectedEof),
+            Err(e) => e,
+        };
+        self.offset += self.buf.len();
+        self.buf.clear();
+        Err(e)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> AsRef<[u8]> for ReadView<R> {
+    fn as_ref(&self) -> &[u8] {
+        &self.buf[self.start..]
+    }
+}
+
+impl<R> Pos for ReadView<R> {
+    fn pos(&self) -> usize {
+        self.offset + self.start
+    }
+}
+
+impl<R> View<u8> for ReadView<R> {
+    fn take(&mut self, n: usize) -> Result<&[u8], Error> {
+        let start = self.start;
+        Error::check(self.pos(), n, self.buf.len() - start)?;
+        self.start += n;
+        Ok(&self.buf[start .. start + n])
+    }
+}
+
+impl<R> Bytes for ReadView<R> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead,Seek,Write};
+    use crate::{BytesMut,VecView};
+
+    #[test]
+    fn test_read() {
+        let mut a = IoView::new(&[1,2,3,4,5][..]);
+        let mut buf = [0; 2];
+
+        assert!(a.read(&mut buf).unwrap() == 2);
+        assert!(buf == [1,2]);
+        assert!(a.fill_buf().unwrap() == [3,4,5]);
+        a.consume(1);
+        let mut rest = Vec::new();
+        assert!(a.read_to_end(&mut rest).unwrap() == 2);
+        assert!(rest == [4,5]);
+        assert!(a.read(&mut buf).unwrap() == 0);
+    }
+
+    #[test]
+    fn test_write() {
+        let mut buf = [0; 4];
+        let mut a = IoView::new(&mut buf[..]);
+
+        assert!(a.write(&[1,2,3]).unwrap() == 3);
+        assert!(a.write(&[4,5]).unwrap() == 1);
+        assert!(a.write_all(&[6]).unwrap_err().kind() == io::ErrorKind::WriteZero);
+        assert!(buf == [1,2,3,4]);
+
+        let mut b = IoView::new(VecView::new());
+        assert!(write!(b, "{}-{}", 12, 34).is_ok());
+        assert!(b.inner_mut().put_u8(b'!').is_ok());
+        assert!(b.into_inner().as_slice() == b"12-34!");
+    }
+
+    #[test]
+    fn test_seek() {
+        let mut a = Look::new(&[1,2,3,4][..]);
+
+        assert!(Seek::seek(&mut a, io::SeekFrom::End(-1)).unwrap() == 3);
+        assert!(a.take_u8() == Ok(4));
+        assert!(Seek::seek(&mut a, io::SeekFrom::Current(-3)).unwrap() == 1);
+        assert!(Seek::seek(&mut a, io::SeekFrom::Start(5)).unwrap_err().kind() == io::ErrorKind::InvalidInput);
+        assert!(a.stream_position().unwrap() == 1);
+    }
+
+    #[test]
+    fn test_records() {
+        let mut a = ReadView::new(&[0,1,0,2,0,3,0][..]);
+
+        assert!(a.next_record(2).unwrap());
+        assert!(a.take_u16_be() == Ok(1));
+        assert!(a.take_u8() == Err(Error::out_of_bounds(2, 1, 0)));
+        assert!(a.next_record(4).unwrap());
+        assert!(a.take_u8() == Ok(0));
+        assert!(a.pos() == 3);
+        assert!(a.next_record(2).unwrap_err().kind() == io::ErrorKind::UnexpectedEof);
+        assert!(a.as_ref().is_empty());
+        assert!(a.pos() == 7, "counts the byte read before the end");
+        assert!(!a.next_record(2).unwrap());
+
+        let e = io::Error::from(Error::out_of_bounds(0, 1, 0));
+        assert!(e.kind() == io::ErrorKind::UnexpectedEof);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::{Error,ErrorKind};
+
+/// Most bytes a LEB128 number of `bits` can take
+const fn max_len(bits: u32) -> usize {
+    bits.div_ceil(7) as usize
+}
+
+/// Decodes an unsigned LEB128 number of `bits` from the start of `buf`. Returns it with its length.
+pub(crate) fn decode_unsigned(buf: &[u8], bits: u32, pos: usize) -> Result<(u64, usize), Error> {
+    let max = max_len(bits);
+    let mut out = 0u64;
+    let mut shift = 0;
+    for (i, byte) in buf.iter().copied().enumerate() {
+        let low = u64::from(byte & 0x7F);
+        if i == max - 1 {
+            if byte & 0x80 != 0 {
+                return Err(Error::new(ErrorKind::Overlong, pos, max + 1, buf.len()))
+            }
+            if low >> (bits - shift) != 0 {
+                return Err(Error::new(ErrorKind::Overflow, pos, i + 1, buf.len()))
+            }
+        }
+        out |= low << shift;
+        if byte & 0x80 == 0 { return Ok((out, i + 1)) }
+        shift += 7;
+    }
+    Err(Error::out_of_bounds(pos, buf.len() + 1, buf.len()))
+}
+
+/// Decodes a signed LEB128 number of `bits` from the start of `buf`. Returns it with its length.
+pub(crate) fn decode_signed(buf: &[u8], bits: u32, pos: usize) -> Result<(i64, usize), Error> {
+    let max = max_len(bits);
+    let mut out = 0i64;
+    let mut shift = 0;
+    for (i, byte) in buf.iter().copied().enumerate() {
+        let low = byte & 0x7F;
+        if i == max - 1 {
+            if byte & 0x80 != 0 {
+                return Err(Error::new(ErrorKind::Overlong, pos, max + 1, buf.len()))
+            }
+            // The unused bits must all copy the sign bit
+            let sign = bits - shift - 1;
+            let mask = (0x7F >> sign) << sign;
+            if low & mask != 0 && low & mask != mask {
+                return Err(Error::new(ErrorKind::Overflow, pos, i + 1, buf.len()))
+            }
+        }
+        out |= i64::from(low) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            if shift < 64 && low & 0x40 != 0 { out |= -1 << shift }
+            return Ok((out, i + 1))
+        }
+    }
+    Err(Error::out_of_bounds(pos, buf.len() + 1, buf.len()))
+}
+
+pub(crate) fn encode_unsigned(mut val: u64, out: &mut [u8; 10]) -> usize {
+    let mut i = 0;
+    loop {
+        let byte = (val & 0x7F) as u8;
+        val >>= 7;
+        if val == 0 {
+            out[i] = byte;
+            return i + 1
+        }
+        out[i] = byte | 0x80;
+        i += 1;
+    }
+}
+
+pub(crate) fn encode_signed(mut val: i64, out: &mut [u8; 10]) -> usize {
+    let mut i = 0;
+    loop {
+        let byte = (val & 0x7F) as u8;
+        val >>= 7;
+        if (val == 0 && byte & 0x40 == 0) || (val == -1 && byte & 0x40 != 0) {
+            out[i] = byte;
+            return i + 1
+        }
+        out[i] = byte | 0x80;
+        i += 1;
+    }
+}
+
+/// Bytes [`put_uleb128`](./trait.BytesMut.html#method.put_uleb128) will write for `val`
+pub fn uleb128_len(val: u64) -> usize {
+    let bits = 64 - val.leading_zeros();
+    max_len(bits.max(1))
+}
+
+/// Bytes [`put_sleb128`](./trait.BytesMut.html#method.put_sleb128) will write for `val`
+pub fn sleb128_len(val: i64) -> usize {
+    let magnitude = if val < 0 { !val } else { val };
+    let bits = 64 - magnitude.leading_zeros() + 1;
+    max_len(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_unsigned() {
+        assert!(decode_unsigned(&[0], 64, 0) == Ok((0, 1)));
+        assert!(decode_unsigned(&[0xE5,0x8E,0x26,0xFF], 64, 0) == Ok((624485, 3)));
+        assert!(decode_unsigned(&[0x80,0x00], 32, 0) == Ok((0, 2)), "padding is allowed");
+
+        let max = [0xFF,0xFF,0xFF,0xFF,0x0F];
+        assert!(decode_unsigned(&max, 32, 0) == Ok((u64::from(u32::MAX), 5)));
+        let big = [0xFF,0xFF,0xFF,0xFF,0x1F];
+        assert!(decode_unsigned(&big, 32, 0).unwrap_err().kind == ErrorKind::Overflow);
+        let long = [0x80,0x80,0x80,0x80,0x80,0x00];
+        assert!(decode_unsigned(&long, 32, 0).unwrap_err().kind == ErrorKind::Overlong);
+
+        let max = [0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0x01];
+        assert!(decode_unsigned(&max, 64, 0) == Ok((u64::MAX, 10)));
+        let big = [0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0x02];
+        assert!(decode_unsigned(&big, 64, 0).unwrap_err().kind == ErrorKind::Overflow);
+
+        assert!(decode_unsigned(&[0x80], 64, 3) == Err(Error::out_of_bounds(3, 2, 1)));
+        assert!(decode_unsigned(&[], 64, 0) == Err(Error::out_of_bounds(0, 1, 0)));
+    }
+
+    #[test]
+    fn test_decode_signed() {
+        assert!(decode_signed(&[0x02], 64, 0) == Ok((2, 1)));
+        assert!(decode_signed(&[0x7E], 64, 0) == Ok((-2, 1)));
+        assert!(decode_signed(&[0xC0,0xBB,0x78], 64, 0) == Ok((-123456, 3)));
+
+        let min = [0x80,0x80,0x80,0x80,0x78];
+        assert!(decode_signed(&min, 32, 0) == Ok((i64::from(i32::MIN), 5)));
+        let max = [0xFF,0xFF,0xFF,0xFF,0x07];
+        assert!(decode_signed(&max, 32, 0) == Ok((i64::from(i32::MAX), 5)));
+        let big = [0xFF,0xFF,0xFF,0xFF,0x0F];
+        assert!(decode_signed(&big, 32, 0).unwrap_err().kind == ErrorKind::Overflow);
+
+        let min = [0x80,0x80,0x80,0x80,0x80,0x80,0x80,0x80,0x80,0x7F];
+        assert!(decode_signed(&min, 64, 0) == Ok((i64::MIN, 10)));
+        let max = [0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0x00];
+        assert!(decode_signed(&max, 64, 0) == Ok((i64::MAX, 10)));
+        let long = [0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0xFF,0x80,0x00];
+        assert!(decode_signed(&long, 64, 0).unwrap_err().kind == ErrorKind::Overlong);
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut buf = [0; 10];
+        for val in [0, 1, 127, 128, 624485, u64::from(u32::MAX), u64::MAX].iter().copied() {
+            let len = encode_unsigned(val, &mut buf);
+            assert!(len == uleb128_len(val));
+            assert!(decode_unsigned(&buf[..len], 64, 0) == Ok((val, len)));
+        }
+        for val in [0, 1, -1, 63, 64, -64, -65, -123456, i64::MIN, i64::MAX].iter().copied() {
+            let len = encode_signed(val, &mut buf);
+            assert!(len == sleb128_len(val));
+            assert!(decode_signed(&buf[..len], 64, 0) == Ok((val, len)));
+        }
+    }
+}
@@ -1,6 +1,6 @@
 //! Extension to slices for buffers
 //!
-//! See the traits [`Bytes`](./trait.Bytes.html), [`BytesMut`](./trait.BytesMut.html) (or the generic versions [`View`](./trait.View.html), [`ViewMut`](./trait.View.html)), and the struct [`Look`](./struct.Look.html).
+//! See the traits [`Bytes`](./trait.Bytes.html), [`BytesMut`](./trait.BytesMut.html) (or the generic versions [`View`](./trait.View.html), [`ViewMut`](./trait.View.html)), and the struct [`Look`](./struct.Look.html). Failures are reported with [`Error`](./struct.Error.html).
 //!
 //! See also [`bytes`](https://docs.rs/bytes), [`byteorder`](https://docs.rs/byteorder), and [`nom`](https://docs.rs/nom).
 
@@ -8,14 +8,61 @@
 
 extern crate core;
 
-/// When an operation goes to a bad position. E.g. out of bounds or invalid UTF8
-#[derive(Debug,PartialEq,Eq)]
-pub struct BadPos;
+#[cfg(feature = "alloc")]
+extern crate alloc;
 
+#[cfg(feature = "std")]
+extern crate std;
+
+mod bit_view;
+mod bit_view_mut;
+mod counter;
+mod endian_int;
+mod endian_view;
+mod error;
+mod fmt_view;
+#[cfg(feature = "std")]
+mod io;
+mod leb128;
+mod limit;
 mod look;
+mod num;
+mod patch;
+mod pod;
+mod prefix;
+mod ring;
+mod segments;
+mod stream;
+mod take_put;
+#[cfg(feature = "alloc")]
+mod vec_view;
 mod view;
 mod view_mut;
 
+pub use bit_view::*;
+pub use bit_view_mut::*;
+pub use counter::*;
+pub use endian_int::*;
+pub use endian_view::*;
+pub use error::*;
+pub use fmt_view::*;
+#[cfg(feature = "std")]
+pub use io::*;
+pub use leb128::{uleb128_len,sleb128_len};
+pub use limit::*;
 pub use look::*;
+pub use num::*;
+pub use patch::*;
+pub use pod::Pod;
+pub use prefix::*;
+pub use ring::*;
+pub use segments::*;
+pub use stream::*;
+pub use take_put::*;
+#[cfg(feature = "alloc")]
+pub use vec_view::*;
 pub use view::*;
-pub use view_mut::*;
\ No newline at end of file
+pub use view_mut::*;
+
+#[cfg(feature = "derive")]
+pub use views_derive::{Take,Put};
@@ -0,0 +1,276 @@
+use core::mem;
+
+use crate::{Bytes,BytesMut,BytesRef,Error,ErrorKind,Patch,Pod,Pos,View,ViewMut,ViewRef,pod};
+
+/// A view that can't go more than a number of elements into another view, e.g. the body of a
+/// chunk with a declared length
+///
+/// Made with [`View::take_view`](./trait.View.html#method.take_view) or
+/// [`Look::limit`](./struct.Look.html#method.limit). Reading or writing moves the underlying view
+/// too, so errors have its positions. Those limits take what's left of the chunk when they're
+/// dropped, so the underlying view always ends up after it. Check the whole chunk was used with
+/// [`expect_empty`](#method.expect_empty), or move past it early with
+/// [`skip_rest`](#method.skip_rest).
+pub struct Limit<'a, V: ?Sized> {
+    view: &'a mut V,
+    left: usize,
+    /// Takes what's left when dropped
+    skip: Option<Skip<V>>,
+}
+
+/// Takes `n` elements from a view
+type Skip<V> = fn(&mut V, usize) -> Result<(), Error>;
+
+impl<'a, V: ?Sized> Limit<'a, V> {
+    /// Limit `view` to `n` elements. It's fine for `view` to have fewer. Dropping the limit leaves
+    /// `view` where it's at.
+    pub fn new(view: &'a mut V, n: usize) -> Limit<'a, V> {
+        Limit { view, left: n, skip: None }
+    }
+
+    /// Limit `view` to `n` elements, and take what's left of them when dropped. This is what
+    /// [`take_view`](./trait.View.html#method.take_view) makes.
+    pub fn skipping<T>(view: &'a mut V, n: usize) -> Limit<'a, V>
+    where
+        V: View<T>
+    {
+        Limit { view, left: n, skip: Some(skip::<T, V>) }
+    }
+
+    /// Elements left before the limit
+    pub fn left(&self) -> usize {
+        self.left
+    }
+
+    /// Fail with [`Trailing`](./enum.ErrorKind.html#variant.Trailing) if anything is left
+    pub fn expect_empty(&self) -> Result<(), Error>
+    where
+        V: Pos
+    {
+        if self.left == 0 { return Ok(()) }
+        Err(Error::new(ErrorKind::Trailing, self.view.pos(), 0, self.left))
+    }
+
+    /// Take what's left before the limit
+    pub fn skip_rest<T>(&mut self) -> Result<(), Error>
+    where
+        V: View<T>
+    {
+        let left = self.left;
+        skip(self, left)
+    }
+
+    fn check(&self, n: usize) -> Result<(), Error>
+    where
+        V: Pos
+    {
+        Error::check(self.view.pos(), n, self.left)
+    }
+}
+
+impl<V: ?Sized> Drop for Limit<'_, V> {
+    fn drop(&mut self) {
+        if let Some(skip) = self.skip {
+            let _ = skip(self.view, self.left);
+        }
+    }
+}
+
+/// Take `n` elements in as many takes as `view` needs, e.g. one chunk at a time from
+/// [`Segments`](./struct.Segments.html)
+fn skip<T, V: View<T> + ?Sized>(view: &mut V, mut n: usize) -> Result<(), Error> {
+    while n > 0 {
+        let step = match view.as_ref().len().min(n) {
+            0 => n,
+            step => step,
+        };
+        view.take(step)?;
+        n -= step;
+    }
+    Ok(())
+}
+
+impl<T, V: AsRef<[T]> + ?Sized> AsRef<[T]> for Limit<'_, V> {
+    fn as_ref(&self) -> &[T] {
+        let buf = self.view.as_ref();
+        &buf[.. self.left.min(buf.len())]
+    }
+}
+
+impl<T, V: AsMut<[T]> + ?Sized> AsMut<[T]> for Limit<'_, V> {
+    fn as_mut(&mut self) -> &mut [T] {
+        let left = self.left;
+        let buf = self.view.as_mut();
+        let n = left.min(buf.len());
+        &mut buf[..n]
+    }
+}
+
+impl<V: Pos + ?Sized> Pos for Limit<'_, V> {
+    fn pos(&self) -> usize {
+        self.view.pos()
+    }
+}
+
+impl<T, V: View<T> + ?Sized> View<T> for Limit<'_, V> {
+    fn take(&mut self, n: usize) -> Result<&[T], Error> {
+        self.check(n)?;
+        let out = self.view.take(n)?;
+        self.left -= n;
+        Ok(out)
+    }
+
+    fn available(&self) -> usize {
+        self.left.min(self.view.available())
+    }
+}
+
+impl<'a, T: 'a, V: ViewRef<'a, T> + ?Sized> ViewRef<'a, T> for Limit<'_, V> {
+    fn take_borrowed(&mut self, n: usize) -> Result<&'a [T], Error> {
+        self.check(n)?;
+        let out = self.view.take_borrowed(n)?;
+        self.left -= n;
+        Ok(out)
+    }
+}
+
+impl<T: Copy, V: ViewMut<T> + ?Sized> ViewMut<T> for Limit<'_, V> {
+    fn put(&mut self, val: &[T]) -> Result<(), Error> {
+        self.check(val.len())?;
+        self.view.put(val)?;
+        self.left -= val.len();
+        Ok(())
+    }
+
+    fn take_slice_mut(&mut self, n: usize) -> Result<&mut [T], Error> {
+        self.check(n)?;
+        let out = self.view.take_slice_mut(n)?;
+        self.left -= n;
+        Ok(out)
+    }
+
+    fn room(&mut self) -> usize {
+        self.left.min(self.view.room())
+    }
+
+    fn reserve(&mut self, n: usize) -> Result<(), Error> {
+        self.check(n)?;
+        self.view.reserve(n)
+    }
+}
+
+/// Casts are checked by the underlying view
+impl<V: Bytes + ?Sized> Bytes for Limit<'_, V> {
+    fn take_slice_of<T: Pod>(&mut self, count: usize) -> Result<&[T], Error> {
+        let size = pod::size_of_slice::<T>(count, self.view.pos())?;
+        self.check(size)?;
+        let out = self.view.take_slice_of::<T>(count)?;
+        self.left -= size;
+        Ok(out)
+    }
+}
+
+impl<V: BytesMut + ?Sized> BytesMut for Limit<'_, V> {
+    fn take_mut<T: Pod>(&mut self) -> Result<&mut T, Error> {
+        self.check(mem::size_of::<T>())?;
+        let out = self.view.take_mut::<T>()?;
+        self.left -= mem::size_of::<T>();
+        Ok(out)
+    }
+}
+
+impl<'a, V: BytesRef<'a> + ?Sized> BytesRef<'a> for Limit<'_, V> {}
+
+impl<V: Patch + ?Sized> Patch for Limit<'_, V> {
+    type Mark = V::Mark;
+
+    fn reserve_bytes(&mut self, n: usize) -> Result<V::Mark, Error> {
+        self.check(n)?;
+        let out = self.view.reserve_bytes(n)?;
+        self.left -= n;
+        Ok(out)
+    }
+
+    fn fill_bytes(&mut self, mark: V::Mark, val: &[u8]) -> Result<(), Error> {
+        self.view.fill_bytes(mark, val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Be,Look,Segments};
+
+    #[test]
+    fn test_take_view() {
+        // Chunks of a tag, a length and a body
+        let buf = [1,3,0xAA,0xBB,0xCC, 2,2,b'h',b'i', 9];
+        let mut a = Look::new(&buf[..]);
+
+        assert!(a.take_u8() == Ok(1));
+        let len = a.take_u8().unwrap() as usize;
+        let mut body = a.take_view(len).unwrap();
+        assert!(body.take_u16_be() == Ok(0xAABB));
+        assert!(body.as_ref() == [0xCC]);
+        assert!(body.take_u16_be() == Err(Error::out_of_bounds(4, 2, 1)));
+        assert!(body.expect_empty() == Err(Error::new(ErrorKind::Trailing, 4, 0, 1)));
+        assert!(body.skip_rest().is_ok());
+        assert!(body.expect_empty().is_ok());
+        drop(body);
+
+        assert!(a.take_u8() == Ok(2));
+        let len = a.take_u8().unwrap() as usize;
+        let name = {
+            let mut body = a.take_view(len).unwrap();
+            let name = body.take_borrowed_as_str(2);
+            assert!(body.expect_empty().is_ok());
+            name
+        };
+        assert!(name == Ok("hi"));
+        assert!(a.take_view(2).is_err());
+        assert!(a.take_u8() == Ok(9));
+    }
+
+    #[test]
+    fn test_limit_put() {
+        let mut buf = [0u8; 8];
+        let mut a = Look::new(&mut buf[..]);
+
+        assert!(a.put_u8(0xFF).is_ok());
+        let mut body = a.limit(4).unwrap();
+        assert!(body.put_u16_be(1).is_ok());
+        assert!(body.put_prefixed::<u8, Be>(b"ab").is_err(), "over the limit");
+        assert!(body.as_mut().len() == 2);
+        assert!(body.put(b"ab").is_ok());
+        assert!(body.put_u8(0) == Err(Error::out_of_bounds(5, 1, 0)));
+        drop(body);
+        assert!(a.pos() == 5);
+        assert!(a.limit(4).is_err());
+        assert!(buf[..5] == [0xFF,0,1,b'a',b'b']);
+    }
+
+    #[test]
+    fn test_drop() {
+        let mut a = &[1,2,3,4,5][..];
+
+        {
+            let mut body = a.take_view(2).unwrap();
+            assert!(body.take_u8() == Ok(1));
+        }
+        assert!(a == [3,4,5], "took the rest of the chunk");
+        drop(a.take_view(0));
+        drop(Limit::new(&mut a, 2));
+        assert!(a == [3,4,5], "only take_view and limit skip");
+
+        let long = [7u8; 20];
+        let chunks: &[&[u8]] = &[&[1], &long, &[2]];
+        let mut b = Segments::new(chunks);
+        {
+            let mut body = b.take_view(21).unwrap();
+            assert!(body.available() == 21);
+            assert!(body.take_view(22).is_err());
+            assert!(body.take_view(21).is_ok(), "more than one chunk");
+        }
+        assert!(b.take_u8() == Ok(2), "skipped across chunks");
+    }
+}
@@ -1,6 +1,19 @@
 use core::marker::PhantomData;
+use core::mem;
 
-use crate::{View,ViewMut,Bytes,BytesMut,BadPos};
+use crate::{View,ViewRef,ViewMut,Bytes,BytesRef,BytesMut,Error,Limit,Patch,Pos};
+
+/// Where to [`seek`](./struct.Look.html#method.seek) to
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum SeekFrom {
+    Start(usize),
+    Current(isize),
+    End(isize),
+}
+
+/// A saved position of a [`Look`](./struct.Look.html)
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Checkpoint(usize);
 
 /// Temporarily look into a buffer
 ///
@@ -18,13 +31,17 @@ impl<T, B: AsRef<[T]>> Look<T, B> {
         Look { buf, pos: 0, phantom: Default::default() }
     }
 
-    pub fn new_with_pos(buf: B, pos: usize) -> Result<Look<T, B>, BadPos> {
+    pub fn new_with_pos(buf: B, pos: usize) -> Result<Look<T, B>, Error> {
         let buf_ref = buf.as_ref();
-        if pos > buf_ref.len() { return Err(BadPos) }
+        Error::check(0, pos, buf_ref.len())?;
         Ok(Look { buf, pos, phantom: Default::default() })
     }
 
-    pub fn from_slice<'a, N: AsRef<[T]> + 'a>(buf: B, slice: &'a [T]) -> Result<Look<T, B>, BadPos> {
+    /// Create a look positioned at `slice`, which must be a part of `buf`.
+    ///
+    /// This is the reverse of [`remaining`](#method.remaining), so
+    /// `Look::from_slice(buf, look.remaining())` is at the same position as `look`.
+    pub fn from_slice(buf: B, slice: &[T]) -> Result<Look<T, B>, Error> {
         let buf_ref = buf.as_ref();
         let buf_start_ptr = buf_ref.as_ptr();
         let buf_end_ptr = unsafe { buf_start_ptr.add(buf_ref.len()) };
@@ -36,10 +53,11 @@ impl<T, B: AsRef<[T]>> Look<T, B> {
             slice_start_ptr > buf_end_ptr ||
             slice_end_ptr > buf_end_ptr
         {
-            return Err(BadPos)
+            return Err(Error::out_of_bounds(0, slice.len(), buf_ref.len()))
         }
 
-        let pos = buf_end_ptr as usize - slice_start_ptr as usize;
+        let size = mem::size_of::<T>().max(1);
+        let pos = (slice_start_ptr as usize - buf_start_ptr as usize) / size;
 
         Ok(Look { buf, pos, phantom: Default::default() })
     }
@@ -48,6 +66,80 @@ impl<T, B: AsRef<[T]>> Look<T, B> {
         self.pos
     }
 
+    /// Move to an absolute position. Positions past the end are out of bounds.
+    pub fn set_pos(&mut self, pos: usize) -> Result<(), Error> {
+        Error::check(self.pos, pos, self.buf.as_ref().len())?;
+        self.pos = pos;
+        Ok(())
+    }
+
+    /// Move relative to the start, the current position, or the end. Returns the new position.
+    /// Positions before the start or past the end are out of bounds.
+    pub fn seek(&mut self, from: SeekFrom) -> Result<usize, Error> {
+        let (base, offset) = match from {
+            SeekFrom::Start(pos) => {
+                self.set_pos(pos)?;
+                return Ok(pos)
+            }
+            SeekFrom::Current(offset) => (self.pos, offset),
+            SeekFrom::End(offset) => (self.buf.as_ref().len(), offset),
+        };
+        let pos = if offset < 0 {
+            let back = offset.unsigned_abs();
+            base.checked_sub(back).ok_or_else(|| Error::out_of_bounds(self.pos, back, base))?
+        } else {
+            base.checked_add(offset as usize).ok_or_else(|| Error::overflow(self.pos))?
+        };
+        self.set_pos(pos)?;
+        Ok(pos)
+    }
+
+    /// Move back to the start of the buffer
+    pub fn rewind(&mut self) {
+        self.pos = 0;
+    }
+
+    /// Save the current position to [`restore`](#method.restore) later
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.pos)
+    }
+
+    /// Go back to a position saved by [`checkpoint`](#method.checkpoint)
+    pub fn restore(&mut self, checkpoint: Checkpoint) -> Result<(), Error> {
+        self.set_pos(checkpoint.0)
+    }
+
+    /// Run `f` and go back to the position it started at if it fails
+    ///
+    /// This makes several takes all-or-nothing. Only the position is restored: bytes already
+    /// written by puts stay in the buffer, and the next puts write over them.
+    pub fn attempt<R, E, F>(&mut self, f: F) -> Result<R, E>
+    where
+        F: FnOnce(&mut Self) -> Result<R, E>
+    {
+        let pos = self.pos;
+        let out = f(self);
+        if out.is_err() { self.pos = pos }
+        out
+    }
+
+    /// Limit the look to the next `n` elements, for reading or writing. Same as
+    /// [`take_view`](./trait.View.html#method.take_view), so dropping it moves past all of them.
+    pub fn limit(&mut self, n: usize) -> Result<Limit<'_, Look<T, B>>, Error> {
+        Error::check(self.pos, n, self.buf.as_ref().len() - self.pos)?;
+        Ok(Limit::skipping(self, n))
+    }
+
+    /// Everything before the current position
+    pub fn consumed(&self) -> &[T] {
+        &self.buf.as_ref()[.. self.pos]
+    }
+
+    /// Everything from the current position on. Same as `as_ref`.
+    pub fn remaining(&self) -> &[T] {
+        &self.buf.as_ref()[self.pos ..]
+    }
+
     pub fn into_inner(self) -> B {
         self.buf
     }
@@ -55,7 +147,7 @@ impl<T, B: AsRef<[T]>> Look<T, B> {
 
 impl<T, B: AsRef<[T]>> AsRef<[T]> for Look<T, B> {
     fn as_ref(&self) -> &[T] {
-        &self.buf.as_ref()[self.pos ..]
+        self.remaining()
     }
 }
 
@@ -65,30 +157,229 @@ impl<T, B: AsMut<[T]>> AsMut<[T]> for Look<T, B> {
     }
 }
 
+impl<T, B> Pos for Look<T, B> {
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
 impl<T, B: AsRef<[T]>> View<T> for Look<T, B> {
-    fn take(&mut self, n: usize) -> Result<&[T], BadPos> {
+    fn take(&mut self, n: usize) -> Result<&[T], Error> {
+        let pos = self.pos;
         let buf = self.buf.as_ref();
-        let len = buf.len();
-        if n > len { return Err(BadPos) }
-        let out = &buf[..n];
+        Error::check(pos, n, buf.len() - pos)?;
+        let out = &buf[pos .. pos + n];
+        self.pos += n;
+        Ok(out)
+    }
+}
+
+impl<'a, T> ViewRef<'a, T> for Look<T, &'a [T]> {
+    fn take_borrowed(&mut self, n: usize) -> Result<&'a [T], Error> {
+        let buf: &'a [T] = self.buf;
+        Error::check(self.pos, n, buf.len() - self.pos)?;
+        let out = &buf[self.pos .. self.pos + n];
         self.pos += n;
         Ok(out)
     }
 }
 
 impl<T: Copy, B: AsMut<[T]>> ViewMut<T> for Look<T, B> {
-    fn put(&mut self, val: &[T]) -> Result<(), BadPos> {
+    fn put(&mut self, val: &[T]) -> Result<(), Error> {
+        let pos = self.pos;
         let buf = self.as_mut();
         let n = val.len();
         let len = buf.len();
-        if n > len { return Err(BadPos) }
+        Error::check(pos, n, len)?;
         let write = &mut buf[..n];
         write.copy_from_slice(val);
         self.pos += n;
         Ok(())
     }
+
+    fn take_slice_mut(&mut self, n: usize) -> Result<&mut [T], Error> {
+        let pos = self.pos;
+        let buf = self.buf.as_mut();
+        Error::check(pos, n, buf.len() - pos)?;
+        self.pos += n;
+        Ok(&mut buf[pos .. pos + n])
+    }
 }
 
 impl<B: AsRef<[u8]>> Bytes for Look<u8, B> {}
 
-impl<B: AsMut<[u8]>> BytesMut for Look<u8, B> {}
\ No newline at end of file
+impl<B: AsMut<[u8]>> BytesMut for Look<u8, B> {}
+
+/// Fields are marked by their position
+impl<B: AsMut<[u8]>> Patch for Look<u8, B> {
+    type Mark = usize;
+
+    fn reserve_bytes(&mut self, n: usize) -> Result<usize, Error> {
+        let pos = self.pos;
+        self.take_slice_mut(n)?;
+        Ok(pos)
+    }
+
+    fn fill_bytes(&mut self, pos: usize, val: &[u8]) -> Result<(), Error> {
+        let buf = self.buf.as_mut();
+        if pos > buf.len() { return Err(Error::out_of_bounds(pos, val.len(), 0)) }
+        Error::check(pos, val.len(), buf.len() - pos)?;
+        buf[pos .. pos + val.len()].copy_from_slice(val);
+        Ok(())
+    }
+}
+
+impl<'a> BytesRef<'a> for Look<u8, &'a [u8]> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take() {
+        let mut a = Look::new(&[1,2,3,4,5][..]);
+
+        assert!(a.take(2) == Ok(&[1,2][..]));
+        assert!(a.pos() == 2);
+        assert!(a.take(2) == Ok(&[3,4][..]), "take from pos");
+        assert!(a.take(2).is_err(), "take past end");
+        assert!(a.pos() == 4, "took none");
+        assert!(a.take(1) == Ok(&[5][..]));
+        assert!(a.as_ref() == &[][..]);
+    }
+
+    #[test]
+    fn test_peek() {
+        let mut a = Look::new(&[1,2,3][..]);
+        a.take(1).unwrap();
+
+        assert!(a.peek(2) == Ok(&[2,3][..]), "peek from pos");
+        assert!(a.peek_u16_le() == Ok(0x0302));
+        assert!(a.peek(3).unwrap_err() == Error::out_of_bounds(1, 3, 2));
+        assert!(a.pos() == 1);
+    }
+
+    #[test]
+    fn test_put() {
+        let mut src = [0u8; 4];
+        let mut a = Look::new(&mut src[..]);
+
+        assert!(a.put(&[1,2]).is_ok());
+        a.rewind();
+        assert!(a.put(&[3]).is_ok(), "put from pos");
+        assert!(a.seek(SeekFrom::End(-1)) == Ok(3));
+        assert!(a.put(&[4]).is_ok());
+        assert!(a.put(&[5]).is_err(), "put past end");
+        assert!(a.into_inner() == &[3,2,0,4][..]);
+    }
+
+    #[test]
+    fn test_seek() {
+        let mut a = Look::new(&[1,2,3,4,5][..]);
+
+        assert!(a.seek(SeekFrom::Start(2)) == Ok(2));
+        assert!(a.take_u8() == Ok(3));
+        assert!(a.seek(SeekFrom::Current(-2)) == Ok(1));
+        assert!(a.take_u8() == Ok(2));
+        assert!(a.seek(SeekFrom::End(-1)) == Ok(4));
+        assert!(a.take_u8() == Ok(5));
+        assert!(a.seek(SeekFrom::End(0)) == Ok(5));
+
+        assert!(a.seek(SeekFrom::Start(6)).is_err(), "past end");
+        assert!(a.seek(SeekFrom::Current(1)).is_err(), "past end");
+        assert!(a.seek(SeekFrom::End(-6)) == Err(Error::out_of_bounds(5, 6, 5)), "before start");
+        assert!(a.pos() == 5, "failed seeks don't move");
+
+        assert!(a.set_pos(0).is_ok());
+        assert!(a.take_u8() == Ok(1));
+        assert!(a.set_pos(6).is_err());
+    }
+
+    #[test]
+    fn test_consumed_remaining() {
+        let mut a = Look::new(&[1,2,3][..]);
+        assert!(a.consumed() == &[][..]);
+        assert!(a.remaining() == &[1,2,3][..]);
+
+        a.take(1).unwrap();
+        assert!(a.consumed() == &[1][..]);
+        assert!(a.remaining() == &[2,3][..]);
+
+        a.rewind();
+        assert!(a.pos() == 0);
+        assert!(a.remaining() == &[1,2,3][..]);
+    }
+
+    #[test]
+    fn test_from_slice() {
+        let buf = &[1u16,2,3,4][..];
+        let mut a = Look::new(buf);
+        a.take(3).unwrap();
+
+        let b = Look::from_slice(buf, a.remaining()).unwrap();
+        assert!(b.pos() == 3);
+        assert!(b.remaining() == &[4][..]);
+
+        let b = Look::from_slice(buf, &buf[1..2]).unwrap();
+        assert!(b.pos() == 1);
+
+        let b = Look::from_slice(buf, &buf[4..]).unwrap();
+        assert!(b.pos() == 4);
+
+        let other = [1u16,2];
+        assert!(Look::from_slice(buf, &other[..]).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint() {
+        let mut a = Look::new(&[1,2,3][..]);
+        let start = a.checkpoint();
+
+        a.take(2).unwrap();
+        let middle = a.checkpoint();
+        assert!(a.restore(start).is_ok());
+        assert!(a.pos() == 0);
+        assert!(a.restore(middle).is_ok());
+        assert!(a.take_u8() == Ok(3));
+
+        let mut b = Look::new(&[1][..]);
+        assert!(b.restore(middle).is_err(), "past end");
+        assert!(b.pos() == 0);
+    }
+
+    #[test]
+    fn test_attempt() {
+        let mut a = Look::new(&[1,2,0][..]);
+
+        let out = a.attempt(|a| Ok::<_, Error>((a.take_u8()?, a.take_u16_le()?)));
+        assert!(out == Ok((1,2)));
+        assert!(a.pos() == 3);
+
+        a.rewind();
+        let out = a.attempt(|a| Ok::<_, Error>((a.take_u8()?, a.take_u32_le()?)));
+        assert!(out.is_err());
+        assert!(a.pos() == 0, "rewound");
+
+        let out = a.attempt(|a| match a.take_u8() {
+            Ok(2) => Ok(()),
+            _ => Err("bad tag"),
+        });
+        assert!(out == Err("bad tag"));
+        assert!(a.pos() == 0, "rewound on any error");
+    }
+
+    #[test]
+    fn test_attempt_put() {
+        let mut src = [0u8; 3];
+        let mut a = Look::new(&mut src[..]);
+
+        let out = a.attempt(|a| { a.put_u8(1)?; a.put_u32_le(2) });
+        assert!(out.is_err());
+        assert!(a.pos() == 0);
+        assert!(a.as_ref() == [1,0,0], "the first put stays");
+
+        let out = a.attempt(|a| { a.put_u8(1)?; a.put_u16_le(2) });
+        assert!(out.is_ok());
+        assert!(a.into_inner() == &[1,2,0][..]);
+    }
+}
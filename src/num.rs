@@ -0,0 +1,106 @@
+/// Numbers that can be read from bytes, e.g. with [`take_num`](./trait.Bytes.html#method.take_num)
+pub trait FromBytes: Sized {
+    /// The byte array, `[u8; size_of::<Self>()]`
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+    fn from_le_bytes(bytes: Self::Bytes) -> Self;
+    fn from_be_bytes(bytes: Self::Bytes) -> Self;
+    fn from_ne_bytes(bytes: Self::Bytes) -> Self;
+}
+
+/// Numbers that can be written as bytes, e.g. with [`put_num`](./trait.BytesMut.html#method.put_num)
+pub trait ToBytes {
+    /// The byte array, `[u8; size_of::<Self>()]`
+    type Bytes: AsRef<[u8]>;
+
+    fn to_le_bytes(self) -> Self::Bytes;
+    fn to_be_bytes(self) -> Self::Bytes;
+    fn to_ne_bytes(self) -> Self::Bytes;
+}
+
+macro_rules! impl_bytes {
+    ($($typ:tt)*) => {
+        $(
+            impl FromBytes for $typ {
+                type Bytes = [u8; core::mem::size_of::<$typ>()];
+
+                fn from_le_bytes(bytes: Self::Bytes) -> Self { $typ::from_le_bytes(bytes) }
+                fn from_be_bytes(bytes: Self::Bytes) -> Self { $typ::from_be_bytes(bytes) }
+                fn from_ne_bytes(bytes: Self::Bytes) -> Self { $typ::from_ne_bytes(bytes) }
+            }
+
+            impl ToBytes for $typ {
+                type Bytes = [u8; core::mem::size_of::<$typ>()];
+
+                fn to_le_bytes(self) -> Self::Bytes { $typ::to_le_bytes(self) }
+                fn to_be_bytes(self) -> Self::Bytes { $typ::to_be_bytes(self) }
+                fn to_ne_bytes(self) -> Self::Bytes { $typ::to_ne_bytes(self) }
+            }
+        )*
+    }
+}
+
+impl_bytes!(u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 f32 f64);
+
+/// A byte order chosen at compile time. See [`Le`](./struct.Le.html), [`Be`](./struct.Be.html)
+/// and [`Ne`](./struct.Ne.html).
+pub trait Endian {
+    fn from_bytes<N: FromBytes>(bytes: N::Bytes) -> N;
+    fn to_bytes<N: ToBytes>(val: N) -> N::Bytes;
+}
+
+/// Little endian
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Le;
+
+/// Big endian
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Be;
+
+/// Native endian
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Ne;
+
+impl Endian for Le {
+    fn from_bytes<N: FromBytes>(bytes: N::Bytes) -> N { N::from_le_bytes(bytes) }
+    fn to_bytes<N: ToBytes>(val: N) -> N::Bytes { val.to_le_bytes() }
+}
+
+impl Endian for Be {
+    fn from_bytes<N: FromBytes>(bytes: N::Bytes) -> N { N::from_be_bytes(bytes) }
+    fn to_bytes<N: ToBytes>(val: N) -> N::Bytes { val.to_be_bytes() }
+}
+
+impl Endian for Ne {
+    fn from_bytes<N: FromBytes>(bytes: N::Bytes) -> N { N::from_ne_bytes(bytes) }
+    fn to_bytes<N: ToBytes>(val: N) -> N::Bytes { val.to_ne_bytes() }
+}
+
+/// A byte order chosen at runtime, e.g. from a file header. See
+/// [`EndianView`](./struct.EndianView.html).
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl Endianness {
+    #[cfg(target_endian = "little")]
+    pub const NATIVE: Endianness = Endianness::Little;
+    #[cfg(target_endian = "big")]
+    pub const NATIVE: Endianness = Endianness::Big;
+
+    pub fn from_bytes<N: FromBytes>(self, bytes: N::Bytes) -> N {
+        match self {
+            Endianness::Little => Le::from_bytes(bytes),
+            Endianness::Big => Be::from_bytes(bytes),
+        }
+    }
+
+    pub fn to_bytes<N: ToBytes>(self, val: N) -> N::Bytes {
+        match self {
+            Endianness::Little => Le::to_bytes(val),
+            Endianness::Big => Be::to_bytes(val),
+        }
+    }
+}
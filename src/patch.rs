@@ -0,0 +1,159 @@
+use core::convert::TryFrom;
+use core::marker::PhantomData;
+use core::mem;
+
+use crate::{BytesMut,Endian,Error,Le,Be,Limit,ToBytes};
+
+/// A number field reserved with [`reserve_num`](./trait.Patch.html#method.reserve_num) to
+/// [`fill_slot`](./trait.Patch.html#method.fill_slot) later
+#[derive(Debug)]
+#[must_use]
+pub struct Slot<M, N, E> {
+    mark: M,
+    phantom: PhantomData<(N, E)>,
+}
+
+/// Byte views that can go back and fill in fields, like a length or a checksum that comes before
+/// the data it covers
+///
+/// Slices reserve by splitting the field off, so their `Mark` is the field itself. Views with a
+/// position, like [`Look`](./struct.Look.html), use that.
+pub trait Patch: BytesMut {
+    /// Where a reserved field is
+    type Mark;
+
+    /// Skip `n` bytes to fill later
+    fn reserve_bytes(&mut self, n: usize) -> Result<Self::Mark, Error>;
+
+    /// Write `val` where `mark` was reserved
+    fn fill_bytes(&mut self, mark: Self::Mark, val: &[u8]) -> Result<(), Error>;
+
+    /// Reserve a number in the byte order `E`, e.g. `reserve_num::<u32, Le>()`
+    fn reserve_num<N: ToBytes, E: Endian>(&mut self) -> Result<Slot<Self::Mark, N, E>, Error> {
+        let mark = self.reserve_bytes(mem::size_of::<N>())?;
+        Ok(Slot { mark, phantom: PhantomData })
+    }
+
+    /// Write `val` into a reserved number. It's named so it doesn't clash with `slice::fill`.
+    fn fill_slot<N: ToBytes, E: Endian>(&mut self, slot: Slot<Self::Mark, N, E>, val: N) -> Result<(), Error> {
+        self.fill_bytes(slot.mark, E::to_bytes(val).as_ref())
+    }
+
+    fn reserve_u16_le(&mut self) -> Result<Slot<Self::Mark, u16, Le>, Error> {
+        self.reserve_num()
+    }
+
+    fn reserve_u16_be(&mut self) -> Result<Slot<Self::Mark, u16, Be>, Error> {
+        self.reserve_num()
+    }
+
+    fn reserve_u32_le(&mut self) -> Result<Slot<Self::Mark, u32, Le>, Error> {
+        self.reserve_num()
+    }
+
+    fn reserve_u32_be(&mut self) -> Result<Slot<Self::Mark, u32, Be>, Error> {
+        self.reserve_num()
+    }
+
+    fn reserve_u64_le(&mut self) -> Result<Slot<Self::Mark, u64, Le>, Error> {
+        self.reserve_num()
+    }
+
+    fn reserve_u64_be(&mut self) -> Result<Slot<Self::Mark, u64, Be>, Error> {
+        self.reserve_num()
+    }
+
+    /// Put what `f` writes after its length as a prefix of type `N`, e.g.
+    /// `put_length_prefixed::<u16, Le, _>(|w| w.put_u8(1))`. Lengths that don't fit in `N` are
+    /// [`Overflow`](./enum.ErrorKind.html#variant.Overflow). If `f` fails the prefix isn't filled.
+    fn put_length_prefixed<N, E, F>(&mut self, f: F) -> Result<(), Error>
+    where
+        Self: Sized,
+        N: ToBytes + TryFrom<usize>,
+        E: Endian,
+        F: FnOnce(&mut Limit<'_, Self>) -> Result<(), Error>
+    {
+        let pos = self.pos();
+        let slot = self.reserve_num::<N, E>()?;
+        let mut body = Limit::new(self, usize::MAX);
+        f(&mut body)?;
+        let len = usize::MAX - body.left();
+        drop(body);
+        let len = N::try_from(len).map_err(|_| Error::overflow(pos))?;
+        self.fill_slot(slot, len)
+    }
+}
+
+impl<'a> Patch for &'a mut [u8] {
+    type Mark = &'a mut [u8];
+
+    fn reserve_bytes(&mut self, n: usize) -> Result<&'a mut [u8], Error> {
+        Error::check(0, n, self.len())?;
+        let (out, rest) = mem::take(self).split_at_mut(n);
+        *self = rest;
+        Ok(out)
+    }
+
+    fn fill_bytes(&mut self, mark: &'a mut [u8], val: &[u8]) -> Result<(), Error> {
+        Error::check(0, val.len(), mark.len())?;
+        mark[..val.len()].copy_from_slice(val);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ErrorKind,Look,ViewMut};
+
+    #[test]
+    fn test_slice() {
+        let mut buf = [0u8; 8];
+        let mut a = &mut buf[..];
+
+        let len = a.reserve_u16_be().unwrap();
+        assert!(a.put(b"abc").is_ok());
+        let sum = a.reserve_u16_le().unwrap();
+        assert!(a.len() == 1);
+        assert!(a.fill_slot(sum, 0x0102).is_ok());
+        assert!(a.fill_slot(len, 3).is_ok());
+        assert!(a.reserve_u16_le().is_err());
+        assert!(buf == [0,3,b'a',b'b',b'c',2,1,0]);
+    }
+
+    #[test]
+    fn test_look() {
+        let mut buf = [0u8; 8];
+        let mut a = Look::new(&mut buf[..]);
+
+        assert!(a.put_u8(0xFF).is_ok());
+        let slot = a.reserve_u32_le().unwrap();
+        assert!(a.put_u8(1).is_ok());
+        assert!(a.fill_slot(slot, a.pos() as u32).is_ok());
+        assert!(a.reserve_u32_le().unwrap_err() == Error::out_of_bounds(6, 4, 2));
+        assert!(buf[..6] == [0xFF,6,0,0,0,1]);
+
+        let mut b = Look::new(&mut buf[..]);
+        assert!(b.fill_bytes(10, &[]) == Err(Error::out_of_bounds(10, 0, 0)), "past the end");
+        assert!(b.fill_bytes(7, &[1,2]) == Err(Error::out_of_bounds(7, 2, 1)));
+    }
+
+    #[test]
+    fn test_put_length_prefixed() {
+        let mut buf = [0u8; 8];
+        let mut a = &mut buf[..];
+
+        let out = a.put_length_prefixed::<u16, Be, _>(|w| {
+            w.put_u8(7)?;
+            w.put_length_prefixed::<u8, Le, _>(|w| w.put(b"hi"))
+        });
+        assert!(out.is_ok());
+        assert!(a.len() == 2);
+        assert!(buf[..6] == [0,4,7,2,b'h',b'i']);
+
+        let mut big = [0u8; 300];
+        let mut b = Look::new(&mut big[..]);
+        let e = b.put_length_prefixed::<u8, Le, _>(|w| w.put(&[0; 256])).unwrap_err();
+        assert!(e.kind == ErrorKind::Overflow);
+    }
+}
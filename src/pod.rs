@@ -0,0 +1,42 @@
+use core::mem;
+
+use crate::{Error,ErrorKind};
+
+/// Plain old data that can be cast from any bytes, see
+/// [`take_ref`](./trait.Bytes.html#method.take_ref)
+///
+/// # Safety
+///
+/// Every bit pattern must be a valid `Self`, and it must have no padding. A `#[repr(C)]` struct of
+/// `Pod` fields without padding between them is `Pod`.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($typ:ty)*) => {
+        $( unsafe impl Pod for $typ {} )*
+    }
+}
+
+impl_pod!(u8 i8 u16 i16 u32 i32 u64 i64 u128 i128 usize isize f32 f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// Checks `bytes` can be cast to `T`s
+pub(crate) fn check_align<T: Pod>(bytes: &[u8], pos: usize) -> Result<(), Error> {
+    check_align_at::<T>(bytes.as_ptr(), bytes.len(), pos)
+}
+
+/// Checks `len` bytes at `ptr` could be cast to `T`s, before they're written
+pub(crate) fn check_align_at<T: Pod>(ptr: *const u8, len: usize, pos: usize) -> Result<(), Error> {
+    let align = mem::align_of::<T>();
+    if ptr as usize & (align - 1) != 0 {
+        let kind = ErrorKind::Misaligned { align };
+        return Err(Error::new(kind, pos, len, len))
+    }
+    Ok(())
+}
+
+/// Bytes taken for `count` `T`s
+pub(crate) fn size_of_slice<T: Pod>(count: usize, pos: usize) -> Result<usize, Error> {
+    mem::size_of::<T>().checked_mul(count).ok_or_else(|| Error::overflow(pos))
+}
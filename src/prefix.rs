@@ -0,0 +1,57 @@
+use core::convert::TryFrom;
+
+use crate::{Bytes,BytesMut,Endian,Error};
+
+/// The type of a length prefix, e.g. `u16` in
+/// [`take_prefixed::<u16, Le>`](./trait.Bytes.html#method.take_prefixed)
+pub trait Prefix {
+    /// Take a length. `E` is ignored by prefixes without a byte order.
+    fn take_len<V: Bytes + ?Sized, E: Endian>(view: &mut V) -> Result<usize, Error>;
+
+    /// Put a length, or fail with [`Overflow`](./enum.ErrorKind.html#variant.Overflow) if it
+    /// doesn't fit
+    fn put_len<V: BytesMut + ?Sized, E: Endian>(view: &mut V, len: usize) -> Result<(), Error>;
+}
+
+/// An unsigned LEB128 length prefix
+#[derive(Debug,Clone,Copy,PartialEq,Eq)]
+pub struct Uleb128;
+
+macro_rules! impl_prefix {
+    ($($typ:tt)*) => {
+        $(
+            impl Prefix for $typ {
+                fn take_len<V: Bytes + ?Sized, E: Endian>(view: &mut V) -> Result<usize, Error> {
+                    let pos = view.pos();
+                    let len = view.peek_num::<$typ, E>()?;
+                    let len = usize::try_from(len).map_err(|_| Error::overflow(pos))?;
+                    view.take_num::<$typ, E>()?;
+                    Ok(len)
+                }
+
+                fn put_len<V: BytesMut + ?Sized, E: Endian>(view: &mut V, len: usize) -> Result<(), Error> {
+                    let len = $typ::try_from(len).map_err(|_| Error::overflow(view.pos()))?;
+                    view.put_num::<$typ, E>(len)
+                }
+            }
+        )*
+    }
+}
+
+impl_prefix!(u8 u16 u32 u64);
+
+impl Prefix for Uleb128 {
+    fn take_len<V: Bytes + ?Sized, E: Endian>(view: &mut V) -> Result<usize, Error> {
+        let pos = view.pos();
+        let mut buf = view.as_ref();
+        let len = buf.take_uleb128_u64().map_err(|e| e.offset(pos))?;
+        let len = usize::try_from(len).map_err(|_| Error::overflow(pos))?;
+        view.take_uleb128_u64()?;
+        Ok(len)
+    }
+
+    fn put_len<V: BytesMut + ?Sized, E: Endian>(view: &mut V, len: usize) -> Result<(), Error> {
+        let len = u64::try_from(len).map_err(|_| Error::overflow(view.pos()))?;
+        view.put_uleb128(len)
+    }
+}
@@ -0,0 +1,348 @@
+use core::mem;
+use core::slice;
+
+use crate::{Bytes,BytesMut,Error,ErrorKind,Pod,Pos,View,ViewMut,pod};
+
+/// Elements near the end of the storage that are copied to be contiguous
+const WINDOW: usize = 16;
+
+/// A fixed-capacity ring buffer that is written with [`ViewMut`](./trait.ViewMut.html) and read
+/// with [`View`](./trait.View.html)
+///
+/// Puts and takes wrap around the end of the storage. Takes of up to 16 elements, like numbers,
+/// are copied together when they cross the end, and longer ones are
+/// [`NotContiguous`](./enum.ErrorKind.html#variant.NotContiguous). Near the end `as_ref` and
+/// `peek` see the next 16 elements, so decoding that looks ahead works across it too, like in
+/// [`Segments`](./struct.Segments.html). The position is the number of elements taken so far.
+pub struct Ring<T, const N: usize> {
+    buf: [T; N],
+    head: usize,
+    len: usize,
+    pos: usize,
+    /// The next elements when they cross the end and fewer than `WINDOW` are before it
+    window: [T; WINDOW],
+    window_len: usize,
+    /// Elements of the last take that crossed the end
+    taken: [T; WINDOW],
+}
+
+impl<T: Copy + Default, const N: usize> Ring<T, N> {
+    pub fn new() -> Ring<T, N> {
+        Ring {
+            buf: [T::default(); N],
+            head: 0,
+            len: 0,
+            pos: 0,
+            window: [T::default(); WINDOW],
+            window_len: 0,
+            taken: [T::default(); WINDOW],
+        }
+    }
+}
+
+impl<T: Copy + Default, const N: usize> Default for Ring<T, N> {
+    fn default() -> Ring<T, N> {
+        Ring::new()
+    }
+}
+
+impl<T, const N: usize> Ring<T, N> {
+    /// Elements that can be taken
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Elements that can be put
+    pub fn spare(&self) -> usize {
+        N - self.len
+    }
+
+    /// Drop every element. The position doesn't change.
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+        self.window_len = 0;
+    }
+
+    /// The elements in order, as the part up to the end of the storage and the part that wrapped
+    /// around to the start
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        let end = self.head + self.len;
+        if end > N {
+            (&self.buf[self.head..], &self.buf[.. end - N])
+        } else {
+            (&self.buf[self.head..end], &[])
+        }
+    }
+
+    /// Move the elements to the start of the storage so they're one slice
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        self.buf.rotate_left(self.head);
+        self.head = 0;
+        self.window_len = 0;
+        &mut self.buf[..self.len]
+    }
+
+    /// Where the next element is put
+    fn tail(&self) -> usize {
+        let tail = self.head + self.len;
+        if tail >= N { tail - N } else { tail }
+    }
+}
+
+impl<T: Copy, const N: usize> Ring<T, N> {
+    /// Copy the next elements into `out` without moving
+    fn copy_to(&self, out: &mut [T]) {
+        let first = out.len().min(N - self.head);
+        let (out_first, out_rest) = out.split_at_mut(first);
+        out_first.copy_from_slice(&self.buf[self.head .. self.head + first]);
+        out_rest.copy_from_slice(&self.buf[.. out_rest.len()]);
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.head += n;
+        if self.head >= N { self.head -= N }
+        self.len -= n;
+        self.pos += n;
+        if self.len == 0 { self.head = 0 }
+        self.settle();
+    }
+
+    /// Fill the window if the elements cross the end soon after the head
+    fn settle(&mut self) {
+        let first = self.as_slices().0.len();
+        if first < WINDOW && self.len > first {
+            let len = self.len.min(WINDOW);
+            let mut window = self.window;
+            self.copy_to(&mut window[..len]);
+            self.window = window;
+            self.window_len = len;
+        } else {
+            self.window_len = 0;
+        }
+    }
+
+    fn split_error(&self, n: usize) -> Error {
+        if n > self.len {
+            Error::out_of_bounds(self.pos, n, self.len)
+        } else {
+            Error::new(ErrorKind::NotContiguous, self.pos, n, self.len)
+        }
+    }
+}
+
+impl<T, const N: usize> AsRef<[T]> for Ring<T, N> {
+    fn as_ref(&self) -> &[T] {
+        if self.window_len > 0 {
+            &self.window[..self.window_len]
+        } else {
+            self.as_slices().0
+        }
+    }
+}
+
+/// The space after the elements, up to the end of the storage or the first element
+impl<T, const N: usize> AsMut<[T]> for Ring<T, N> {
+    fn as_mut(&mut self) -> &mut [T] {
+        let tail = self.tail();
+        let end = if self.head + self.len < N { N } else { self.head };
+        &mut self.buf[tail..end]
+    }
+}
+
+impl<T, const N: usize> Pos for Ring<T, N> {
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<T: Copy, const N: usize> View<T> for Ring<T, N> {
+    fn take(&mut self, n: usize) -> Result<&[T], Error> {
+        Error::check(self.pos, n, self.len)?;
+        let start = self.head;
+        if start + n <= N {
+            self.advance(n);
+            return Ok(&self.buf[start .. start + n])
+        }
+        if n > WINDOW { return Err(self.split_error(n)) }
+        let mut taken = self.taken;
+        self.copy_to(&mut taken[..n]);
+        self.taken = taken;
+        self.advance(n);
+        Ok(&self.taken[..n])
+    }
+
+    fn peek(&self, n: usize) -> Result<&[T], Error> {
+        let buf = self.as_ref();
+        if n > buf.len() { return Err(self.split_error(n)) }
+        Ok(&buf[..n])
+    }
+
+    fn available(&self) -> usize {
+        self.len
+    }
+}
+
+impl<T: Copy, const N: usize> ViewMut<T> for Ring<T, N> {
+    fn put(&mut self, val: &[T]) -> Result<(), Error> {
+        let n = val.len();
+        Error::check(self.pos, n, self.spare())?;
+        let tail = self.tail();
+        let first = n.min(N - tail);
+        self.buf[tail .. tail + first].copy_from_slice(&val[..first]);
+        self.buf[.. n - first].copy_from_slice(&val[first..]);
+        self.len += n;
+        self.settle();
+        Ok(())
+    }
+
+    /// A slot that would cross the end of the storage, or come after elements that do, first
+    /// moves the elements to the start with [`make_contiguous`](#method.make_contiguous)
+    fn take_slice_mut(&mut self, n: usize) -> Result<&mut [T], Error> {
+        Error::check(self.pos, n, self.spare())?;
+        if self.head + self.len + n > N { self.make_contiguous(); }
+        let tail = self.tail();
+        self.len += n;
+        Ok(&mut self.buf[tail .. tail + n])
+    }
+
+    fn room(&mut self) -> usize {
+        self.spare()
+    }
+
+    /// Check the whole spare space, including the part that wraps around
+    fn reserve(&mut self, n: usize) -> Result<(), Error> {
+        Error::check(self.pos, n, self.spare())
+    }
+}
+
+impl<const N: usize> Bytes for Ring<u8, N> {
+    /// Casts are only made from the part before the end of the storage, so the alignment is
+    /// checked where the bytes are rather than in the window
+    fn take_slice_of<T: Pod>(&mut self, count: usize) -> Result<&[T], Error> {
+        let size = pod::size_of_slice::<T>(count, self.pos)?;
+        let first = self.as_slices().0;
+        if size > first.len() { return Err(self.split_error(size)) }
+        pod::check_align::<T>(&first[..size], self.pos)?;
+        let out = self.take(size)?;
+        Ok(unsafe { slice::from_raw_parts(out.as_ptr() as *const T, count) })
+    }
+}
+
+impl<const N: usize> BytesMut for Ring<u8, N> {
+    /// Moves the elements like [`take_slice_mut`](#method.take_slice_mut) first, so the alignment
+    /// is checked where the slot will be
+    fn take_mut<T: Pod>(&mut self) -> Result<&mut T, Error> {
+        let size = mem::size_of::<T>();
+        Error::check(self.pos, size, self.spare())?;
+        if self.head + self.len + size > N { self.make_contiguous(); }
+        let tail = self.tail();
+        pod::check_align::<T>(&self.buf[tail .. tail + size], self.pos)?;
+        let out = self.take_slice_mut(size)?;
+        Ok(unsafe { &mut *(out.as_mut_ptr() as *mut T) })
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::op_ref)]
+mod tests {
+    use super::*;
+    use crate::Be;
+
+    #[test]
+    fn test_wrap() {
+        let mut a = Ring::<u8, 6>::new();
+
+        assert!(a.put(&[1,2,3,4]).is_ok());
+        assert!(a.take(3) == Ok(&[1,2,3][..]));
+        assert!(a.put_u32_be(0x0506_0708).is_ok());
+        assert!(a.as_slices() == (&[4,5,6][..], &[7,8][..]));
+        assert!(a.put(&[9,10]) == Err(Error::out_of_bounds(3, 2, 1)));
+        assert!(a.take_u8() == Ok(4));
+        assert!(a.peek_u32_be() == Ok(0x0506_0708), "across the end");
+        assert!(a.take_u32_be() == Ok(0x0506_0708), "across the end");
+        assert!(a.is_empty());
+        assert!(a.pos() == 8);
+    }
+
+    #[test]
+    fn test_multi_put() {
+        let mut a = Ring::<u8, 5>::new();
+
+        assert!(a.put(&[0,0,0,1]).is_ok());
+        assert!(a.take(3).is_ok());
+        assert!(a.as_mut().len() == 1);
+        assert!(a.put_prefixed::<u8, Be>(b"abc").is_ok(), "spare space wraps");
+        assert!(a.spare() == 0);
+        assert!(a.take_u8() == Ok(1));
+        assert!(a.as_slices() == (&[3][..], &b"abc"[..]));
+        assert!(a.take_prefixed::<u8, Be>() == Ok(&b"abc"[..]), "looks ahead across the end");
+    }
+
+    #[test]
+    fn test_long_across() {
+        let mut a = Ring::<u8, 40>::new();
+
+        assert!(a.put(&[0; 30]).is_ok());
+        assert!(a.take(25).is_ok());
+        assert!(a.put(&[1; 20]).is_ok());
+        assert!(a.as_slices().1.len() == 10);
+        assert!(a.available() == 25);
+        assert!(a.peek(17).unwrap_err().kind == ErrorKind::NotContiguous);
+        assert!(a.peek(26).unwrap_err().kind == ErrorKind::OutOfBounds);
+        assert!(a.take(20) == Err(Error::new(ErrorKind::NotContiguous, 25, 20, 25)));
+        assert!(a.take(16).is_ok(), "copied");
+        assert!(a.as_slices() == (&[1; 9][..], &[][..]));
+    }
+
+    #[test]
+    fn test_casts() {
+        let mut a = Ring::<u8, 8>::new();
+        assert!(a.put(&[0; 8]).is_ok());
+        assert!(a.take(7).is_ok());
+        assert!(a.put(&[1,0,0]).is_ok());
+        assert!(a.take_ref::<u16>().unwrap_err().kind == ErrorKind::NotContiguous);
+        assert!(a.take_u16_le() == Ok(0x0100), "copied");
+
+        let mut b = Ring::<u8, 8>::new();
+        let base = b.as_mut().as_ptr() as usize;
+        let odd = 1 - base % 2;
+        assert!(b.put(&[0; 4]).is_ok());
+        assert!(b.take(odd).is_ok());
+        assert!(b.take_ref::<u16>().unwrap_err().kind == ErrorKind::Misaligned { align: 2 });
+        assert!(b.pos() == odd, "took none");
+        assert!(b.take_u8().is_ok());
+        assert!(b.take_ref::<u16>() == Ok(&0));
+
+        let mut c = Ring::<u8, 8>::new();
+        let aligned = (4 - c.as_mut().as_ptr() as usize % 4) % 4;
+        assert!(c.put(&[5; 8][.. aligned + 1]).is_ok());
+        assert!(c.take_mut::<u32>().unwrap_err().kind == ErrorKind::Misaligned { align: 4 });
+        assert!(c.len() == aligned + 1, "took none");
+        let mut d = Ring::<u8, 8>::new();
+        assert!(d.put(&[5; 8][..aligned]).is_ok());
+        assert!(d.take_mut::<u32>().map(|x| *x = 9).is_ok());
+        assert!(d.take(aligned).is_ok());
+        assert!(d.take_u32_ne() == Ok(9));
+    }
+
+    #[test]
+    fn test_take_slice_mut() {
+        let mut a = Ring::<u8, 4>::new();
+
+        assert!(a.put(&[1,2,3]).is_ok());
+        assert!(a.take(2).is_ok());
+        let slot = a.take_slice_mut(3).unwrap();
+        slot.copy_from_slice(&[4,5,6]);
+        assert!(a.as_slices() == (&[3,4,5,6][..], &[][..]));
+        assert!(a.take_slice_mut(1).is_err());
+    }
+}
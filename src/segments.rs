@@ -0,0 +1,326 @@
+use core::slice;
+
+use crate::{Bytes,BytesRef,Endian,Error,ErrorKind,Pod,Pos,Prefix,View,ViewRef,pod};
+
+/// Bytes near a chunk boundary that are copied to be contiguous, enough for any number
+const WINDOW: usize = 16;
+
+/// A byte view over several chunks, e.g. packets as they arrived
+///
+/// Takes of up to 16 bytes, like numbers, are copied together when they span chunks. Longer ones
+/// only borrow from one chunk and are [`NotContiguous`](./enum.ErrorKind.html#variant.NotContiguous)
+/// otherwise. Near a boundary `as_ref` and `peek` see the next 16 bytes, so decoding that looks
+/// ahead, like LEB128, works across chunks too. Reads that are there but can't be seen at once,
+/// like a long `peek` or a NUL-terminated string past the window, are `NotContiguous` rather than
+/// out of bounds or missing their terminator.
+pub struct Segments<'a> {
+    chunks: &'a [&'a [u8]],
+    /// Position in the first chunk
+    offset: usize,
+    pos: usize,
+    remaining: usize,
+    /// The next bytes when the current chunk has fewer than `WINDOW` left
+    window: [u8; WINDOW],
+    window_len: usize,
+    /// Bytes of the last take that spanned chunks
+    taken: [u8; WINDOW],
+}
+
+impl<'a> Segments<'a> {
+    pub fn new(chunks: &'a [&'a [u8]]) -> Segments<'a> {
+        let remaining = chunks.iter().map(|chunk| chunk.len()).sum();
+        let mut out = Segments {
+            chunks,
+            offset: 0,
+            pos: 0,
+            remaining,
+            window: [0; WINDOW],
+            window_len: 0,
+            taken: [0; WINDOW],
+        };
+        out.settle();
+        out
+    }
+
+    /// Bytes left in all chunks
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Take `n` bytes that must be in the current chunk. The result borrows from the chunk.
+    pub fn take_contiguous(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        let chunk = self.chunk();
+        if n > chunk.len() { return Err(self.split_error(n)) }
+        self.advance(n);
+        Ok(&chunk[..n])
+    }
+
+    /// What's left of the current chunk
+    fn chunk(&self) -> &'a [u8] {
+        match self.chunks.first() {
+            Some(chunk) => &chunk[self.offset..],
+            None => &[],
+        }
+    }
+
+    fn split_error(&self, n: usize) -> Error {
+        if n > self.remaining {
+            Error::out_of_bounds(self.pos, n, self.remaining)
+        } else {
+            Error::new(ErrorKind::NotContiguous, self.pos, n, self.remaining)
+        }
+    }
+
+    /// All the bytes left, across chunks
+    fn bytes(&self) -> impl Iterator<Item = u8> + 'a {
+        let rest = self.chunks.get(1..).unwrap_or(&[]);
+        self.chunk().iter().chain(rest.iter().flat_map(|chunk| chunk.iter())).copied()
+    }
+
+    /// Finds the NUL ending the string at the start, like the default of
+    /// [`take_until_nul`](./trait.Bytes.html#method.take_until_nul) but looking past the window
+    fn nul_position(&self) -> Result<usize, Error> {
+        if let Some(len) = self.as_ref().iter().position(|x| *x == b'\0') { return Ok(len) }
+        let left = self.remaining;
+        match self.bytes().position(|x| x == b'\0') {
+            Some(len) => Err(Error::new(ErrorKind::NotContiguous, self.pos, len + 1, left)),
+            None => Err(Error::new(ErrorKind::MissingTerminator, self.pos, left + 1, left)),
+        }
+    }
+
+    /// Copy the next bytes into `out` without moving
+    fn copy_to(&self, out: &mut [u8]) {
+        let mut filled = 0;
+        let mut offset = self.offset;
+        for chunk in self.chunks {
+            let next = &chunk[offset..];
+            let n = next.len().min(out.len() - filled);
+            out[filled .. filled + n].copy_from_slice(&next[..n]);
+            filled += n;
+            offset = 0;
+            if filled == out.len() { break }
+        }
+    }
+
+    fn advance(&mut self, mut n: usize) {
+        self.pos += n;
+        self.remaining -= n;
+        while n > 0 {
+            let left = self.chunks[0].len() - self.offset;
+            if n < left {
+                self.offset += n;
+                break
+            }
+            n -= left;
+            self.chunks = &self.chunks[1..];
+            self.offset = 0;
+        }
+        self.settle();
+    }
+
+    /// Skip finished chunks and fill the window if the current chunk is near its end
+    fn settle(&mut self) {
+        while let Some(chunk) = self.chunks.first() {
+            if self.offset < chunk.len() { break }
+            self.chunks = &self.chunks[1..];
+            self.offset = 0;
+        }
+        let chunk = self.chunk().len();
+        if chunk < WINDOW && self.remaining > chunk {
+            let len = self.remaining.min(WINDOW);
+            let mut window = [0; WINDOW];
+            self.copy_to(&mut window[..len]);
+            self.window = window;
+            self.window_len = len;
+        } else {
+            self.window_len = 0;
+        }
+    }
+}
+
+impl AsRef<[u8]> for Segments<'_> {
+    fn as_ref(&self) -> &[u8] {
+        if self.window_len > 0 {
+            &self.window[..self.window_len]
+        } else {
+            self.chunk()
+        }
+    }
+}
+
+impl Pos for Segments<'_> {
+    fn pos(&self) -> usize {
+        self.pos
+    }
+}
+
+impl View<u8> for Segments<'_> {
+    fn take(&mut self, n: usize) -> Result<&[u8], Error> {
+        if n <= self.chunk().len() {
+            return self.take_contiguous(n)
+        }
+        if n > self.remaining || n > WINDOW { return Err(self.split_error(n)) }
+        let mut taken = [0; WINDOW];
+        self.copy_to(&mut taken[..n]);
+        self.taken = taken;
+        self.advance(n);
+        Ok(&self.taken[..n])
+    }
+
+    fn peek(&self, n: usize) -> Result<&[u8], Error> {
+        let buf = self.as_ref();
+        if n > buf.len() { return Err(self.split_error(n)) }
+        Ok(&buf[..n])
+    }
+
+    fn available(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<'a> ViewRef<'a, u8> for Segments<'a> {
+    fn take_borrowed(&mut self, n: usize) -> Result<&'a [u8], Error> {
+        self.take_contiguous(n)
+    }
+}
+
+impl Bytes for Segments<'_> {
+    /// Like the default, but a length within what's left is never out of bounds
+    fn take_prefixed_max<L: Prefix, E: Endian>(&mut self, max: usize) -> Result<&[u8], Error> {
+        let pos = self.pos;
+        let mut buf = self.as_ref();
+        let len = L::take_len::<_, E>(&mut buf).map_err(|e| e.offset(pos))?;
+        let prefix = self.as_ref().len() - buf.len();
+        let left = self.remaining - prefix;
+        if len > max {
+            return Err(Error::new(ErrorKind::TooLong { max }, pos + prefix, len, left))
+        }
+        Error::check(pos + prefix, len, left)?;
+        Ok(&self.take(prefix + len)?[prefix..])
+    }
+
+    fn take_until_nul(&mut self) -> Result<&[u8], Error> {
+        let len = self.nul_position()?;
+        Ok(&self.take(len + 1)?[..len])
+    }
+
+    /// Casts are only made from one chunk, so the alignment is checked where the bytes are
+    /// rather than in the window
+    fn take_slice_of<T: Pod>(&mut self, count: usize) -> Result<&[T], Error> {
+        let pos = self.pos;
+        let size = pod::size_of_slice::<T>(count, pos)?;
+        let chunk = self.chunk();
+        if size > chunk.len() { return Err(self.split_error(size)) }
+        pod::check_align::<T>(&chunk[..size], pos)?;
+        let out = self.take_contiguous(size)?;
+        Ok(unsafe { slice::from_raw_parts(out.as_ptr() as *const T, count) })
+    }
+
+    fn peek_until_nul(&self) -> Result<&[u8], Error> {
+        let len = self.nul_position()?;
+        Ok(&self.peek(len + 1)?[..len])
+    }
+}
+
+impl<'a> BytesRef<'a> for Segments<'a> {
+    fn take_borrowed_until_nul(&mut self) -> Result<&'a [u8], Error> {
+        let len = self.nul_position()?;
+        Ok(&self.take_borrowed(len + 1)?[..len])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Be;
+
+    #[test]
+    fn test_take_across() {
+        let chunks: &[&[u8]] = &[&[1,2,3], &[], &[4], &[5,6,7,8,9]];
+        let mut a = Segments::new(chunks);
+
+        assert!(a.take_u16_be() == Ok(0x0102));
+        assert!(a.peek_u32_be() == Ok(0x0304_0506));
+        assert!(a.take_u32_be() == Ok(0x0304_0506));
+        assert!(a.pos() == 6);
+        assert!(a.remaining() == 3);
+        assert!(a.take(4) == Err(Error::out_of_bounds(6, 4, 3)));
+        assert!(a.take(3) == Ok(&[7,8,9][..]));
+        assert!(a.as_ref().is_empty());
+    }
+
+    #[test]
+    fn test_take_contiguous() {
+        let long = [7u8; 20];
+        let chunks: &[&[u8]] = &[b"abc", b"de", &long];
+        let mut a = Segments::new(chunks);
+
+        let e = a.take_contiguous(4).unwrap_err();
+        assert!(e == Error::new(ErrorKind::NotContiguous, 0, 4, 25));
+        assert!(a.take_borrowed_as_str(2) == Ok("ab"));
+        assert!(a.take(2) == Ok(&b"cd"[..]), "copied");
+        assert!(a.take(18).unwrap_err().kind == ErrorKind::NotContiguous, "too long to copy");
+        assert!(a.take_contiguous(1) == Ok(&b"e"[..]));
+        assert!(a.take_contiguous(20) == Ok(&long[..]));
+    }
+
+    #[test]
+    fn test_look_ahead() {
+        // 300 as LEB128 split over chunks, then a prefixed string
+        let chunks: &[&[u8]] = &[&[0xAC], &[0x02, 3, b'a'], b"bc"];
+        let mut a = Segments::new(chunks);
+
+        assert!(a.take_uleb128_u32() == Ok(300));
+        assert!(a.take_prefixed::<u8, Be>() == Ok(&b"abc"[..]));
+    }
+
+    #[test]
+    fn test_past_the_window() {
+        let long = [b'a'; 40];
+        let chunks: &[&[u8]] = &[&long[..20], b"b\0"];
+        let mut a = Segments::new(chunks);
+
+        let e = a.take_until_nul().unwrap_err();
+        assert!(e == Error::new(ErrorKind::NotContiguous, 0, 22, 22), "the NUL is there");
+        assert!(!e.streaming().is_incomplete());
+        assert!(a.take_view(23).err() == Some(Error::out_of_bounds(0, 23, 22)));
+        assert!(a.take_view(22).map(|v| v.left()) == Ok(22));
+
+        let chunks: &[&[u8]] = &[&long, b"xy"];
+        let mut b = Segments::new(chunks);
+        assert!(b.take(10).is_ok());
+        assert!(b.peek(30).is_ok());
+        assert!(b.peek(31).unwrap_err().kind == ErrorKind::NotContiguous);
+        assert!(b.peek(33).unwrap_err().kind == ErrorKind::OutOfBounds);
+        assert!(b.take_until_nul().unwrap_err().kind == ErrorKind::MissingTerminator);
+        assert!(b.take_view(32).map(|v| v.left()) == Ok(32));
+    }
+
+    #[test]
+    fn test_take_ref() {
+        #[repr(C, align(4))]
+        struct Aligned([u8; 8]);
+        let src = Aligned([0, 1, 0, 0, 7, 0, 0, 0]);
+        let chunks: &[&[u8]] = &[&src.0[..2], &src.0[2..]];
+        let mut a = Segments::new(chunks);
+
+        assert!(a.take_u8() == Ok(0));
+        assert!(a.take_ref::<u16>().unwrap_err().kind == ErrorKind::NotContiguous);
+        assert!(a.take(2).is_ok());
+        assert!(a.take_ref::<u32>().unwrap_err().kind == ErrorKind::Misaligned { align: 4 });
+        assert!(a.pos() == 3, "took none");
+        assert!(a.take_u8() == Ok(0));
+        assert!(a.take_ref::<u32>() == Ok(&u32::from_ne_bytes([7, 0, 0, 0])));
+    }
+
+    #[test]
+    fn test_long_prefixed() {
+        let chunks: &[&[u8]] = &[&[20], &[7; 10], &[7; 10]];
+        let mut a = Segments::new(chunks);
+
+        let e = a.take_prefixed::<u8, Be>().unwrap_err();
+        assert!(e.kind == ErrorKind::NotContiguous, "all 20 are there");
+        let e = a.take_prefixed_max::<u8, Be>(4).unwrap_err();
+        assert!(e == Error::new(ErrorKind::TooLong { max: 4 }, 1, 20, 20));
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{Error,ErrorKind,Look};
+
+/// Buffers input as it arrives and decodes from it once there's enough
+///
+/// [`feed`](#method.feed) appends bytes to a fixed buffer `B`, e.g. `[u8; 512]`.
+/// [`decode`](#method.decode) runs a decoder over what's buffered. If it runs out of input nothing
+/// is used up and the error is [`Incomplete`](./enum.ErrorKind.html#variant.Incomplete), so it
+/// can be retried after feeding more. Running out anywhere counts, so a decoder shouldn't check
+/// lengths by taking past the end of a nested view. If the input it needs could never fit in the
+/// buffer the error is [`TooLong`](./enum.ErrorKind.html#variant.TooLong) instead, so retrying
+/// can't loop forever.
+pub struct Stream<B> {
+    buf: B,
+    start: usize,
+    end: usize,
+}
+
+impl<B: AsRef<[u8]> + AsMut<[u8]>> Stream<B> {
+    pub fn new(buf: B) -> Stream<B> {
+        Stream { buf, start: 0, end: 0 }
+    }
+
+    /// Bytes fed but not decoded yet
+    pub fn buffered(&self) -> &[u8] {
+        &self.buf.as_ref()[self.start..self.end]
+    }
+
+    /// Room left for [`feed`](#method.feed)
+    pub fn spare(&self) -> usize {
+        self.buf.as_ref().len() - (self.end - self.start)
+    }
+
+    /// Append as much of `input` as fits. Returns how many bytes were used.
+    pub fn feed(&mut self, input: &[u8]) -> usize {
+        let buf = self.buf.as_mut();
+        if self.start > 0 && self.end + input.len() > buf.len() {
+            buf.copy_within(self.start..self.end, 0);
+            self.end -= self.start;
+            self.start = 0;
+        }
+        let n = input.len().min(buf.len() - self.end);
+        buf[self.end .. self.end + n].copy_from_slice(&input[..n]);
+        self.end += n;
+        n
+    }
+
+    /// Decode with `f` from the buffered bytes. The bytes it takes are used up if it succeeds.
+    /// Positions in errors count from the first buffered byte.
+    pub fn decode<'a, T, F>(&'a mut self, f: F) -> Result<T, Error>
+    where
+        F: FnOnce(&mut Look<u8, &'a [u8]>) -> Result<T, Error>
+    {
+        let spare = self.spare();
+        let Stream { buf, start, end } = self;
+        let buf: &'a B = buf;
+        let max = buf.as_ref().len();
+        let mut look = Look::new(&buf.as_ref()[*start..*end]);
+        let out = f(&mut look).map_err(|e| {
+            let e = e.streaming();
+            match e.kind {
+                ErrorKind::Incomplete { needed } if needed > spare => {
+                    Error::new(ErrorKind::TooLong { max }, e.pos, e.requested, e.available)
+                }
+                _ => e,
+            }
+        })?;
+        *start += look.pos();
+        if start == end {
+            *start = 0;
+            *end = 0;
+        }
+        Ok(out)
+    }
+
+    /// Drop the buffered bytes, e.g. after a decoding error
+    pub fn clear(&mut self) {
+        self.start = 0;
+        self.end = 0;
+    }
+
+    pub fn into_inner(self) -> B {
+        self.buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Be,BitOrder,BitView,Bytes,BytesRef,ErrorKind};
+
+    fn frame<'a>(look: &mut Look<u8, &'a [u8]>) -> Result<(u8, &'a str), Error> {
+        let tag = look.take_u8()?;
+        let len = look.take_u16_be()?;
+        Ok((tag, look.take_borrowed_as_str(len as usize)?))
+    }
+
+    #[test]
+    fn test_feed_decode() {
+        let mut a = Stream::new([0u8; 8]);
+
+        assert!(a.feed(&[7,0]) == 2);
+        assert!(a.decode(frame).unwrap_err().kind == ErrorKind::Incomplete { needed: 1 });
+        assert!(a.feed(&[3,b'a']) == 2);
+        assert!(a.decode(frame).unwrap_err().kind == ErrorKind::Incomplete { needed: 2 });
+        assert!(a.buffered() == [7,0,3,b'a']);
+        assert!(a.feed(b"bc\x01\x00") == 4);
+        assert!(a.decode(frame) == Ok((7, "abc")));
+        assert!(a.buffered() == [1,0]);
+
+        assert!(a.feed(b"\x01xyz") == 4);
+        assert!(a.decode(frame) == Ok((1, "x")));
+        assert!(a.spare() == 6, "room after the decoded bytes");
+        assert!(a.feed(b"123456789") == 6);
+        assert!(a.buffered() == b"yz123456");
+    }
+
+    #[test]
+    fn test_malformed() {
+        let mut a = Stream::new([0u8; 4]);
+
+        assert!(a.feed(&[0xFF,0xFF]) == 2);
+        let e = a.decode(|look| look.take_uleb128_u32()).unwrap_err();
+        assert!(e.is_incomplete());
+        assert!(a.feed(&[0xFF,0xFF]) == 2);
+        let e = a.decode(|look| look.take_as_str(4).map(str::len)).unwrap_err();
+        assert!(e.kind == ErrorKind::InvalidUtf8 { valid_up_to: 0 });
+        assert!(a.feed(&[0xFF]) == 0, "full");
+        let e = a.decode(|look| look.take_prefixed::<u8, Be>().map(<[u8]>::len)).unwrap_err();
+        assert!(e == Error::new(ErrorKind::TooLong { max: 4 }, 1, 255, 3), "longer than the buffer");
+        a.clear();
+        assert!(a.buffered().is_empty());
+
+        assert!(a.feed(&[200,1]) == 2);
+        let e = a.decode(|look| look.take_prefixed::<u8, Be>().map(<[u8]>::len)).unwrap_err();
+        assert!(e.kind == ErrorKind::TooLong { max: 4 });
+        assert!(!e.is_incomplete());
+        assert!(a.buffered() == [200,1], "nothing used up");
+
+        let e = Error::out_of_bounds(3, 4, 1).streaming();
+        assert!(e == Error::new(ErrorKind::Incomplete { needed: 3 }, 3, 4, 1));
+        let e = Error::new(ErrorKind::OutOfBits, 3, 20, 5).streaming();
+        assert!(e.kind == ErrorKind::Incomplete { needed: 2 }, "in bytes");
+    }
+
+    #[test]
+    fn test_bits() {
+        let mut a = Stream::new([0u8; 4]);
+
+        assert!(a.feed(&[0xAB]) == 1);
+        let decode = |look: &mut Look<u8, &[u8]>| {
+            BitView::new(look.remaining(), BitOrder::MsbFirst).take_bits(20)
+        };
+        assert!(a.decode(decode).unwrap_err().kind == ErrorKind::Incomplete { needed: 2 });
+        assert!(a.feed(&[0xCD,0xEF]) == 2);
+        assert!(a.decode(decode) == Ok(0xABCDE));
+        assert!(Error::overflow(0).streaming().kind == ErrorKind::Overflow);
+    }
+}
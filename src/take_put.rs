@@ -0,0 +1,88 @@
+use crate::{Bytes,BytesMut,Error};
+
+/// Types that can be taken from a view `V`
+///
+/// With the `derive` feature this can be derived for structs and enums. Fields are taken in
+/// order, and these attributes change how:
+///
+/// - `#[views(le)]`, `#[views(be)]` or `#[views(ne)]` takes a number in that byte order. On the
+///   container it's the default for every field and the enum tag.
+/// - `#[views(len = "count")]` takes a `&[u8]` or `&str` whose length is the earlier field `count`.
+/// - `#[views(until_nul)]` takes a `&[u8]` or `&str` ending with a NUL.
+/// - `#[views(tag = 3)]` on a variant is the tag that comes before its fields. The tag type is
+///   `u8` unless the enum has `#[views(tag_type = "u16")]`.
+///
+/// A derived `Put` checks that `len` fields match their slice and that `until_nul` fields have
+/// no NUL, so what it puts can be taken back. It fails with
+/// [`LengthMismatch`](./enum.ErrorKind.html#variant.LengthMismatch) or
+/// [`InteriorNul`](./enum.ErrorKind.html#variant.InteriorNul) before putting the field.
+///
+/// Fields without attributes are taken with their own `Take`. Types with a lifetime are taken
+/// from a [`BytesRef`](./trait.BytesRef.html) so borrowed fields can outlive the view.
+pub trait Take<V: ?Sized>: Sized {
+    fn take_from(view: &mut V) -> Result<Self, Error>;
+}
+
+/// Types that can be put into a view `V`. See [`Take`](./trait.Take.html) for deriving it.
+pub trait Put<V: ?Sized> {
+    fn put_into(&self, view: &mut V) -> Result<(), Error>;
+}
+
+impl<V: Bytes + ?Sized> Take<V> for u8 {
+    fn take_from(view: &mut V) -> Result<u8, Error> {
+        view.take_u8()
+    }
+}
+
+impl<V: BytesMut + ?Sized> Put<V> for u8 {
+    fn put_into(&self, view: &mut V) -> Result<(), Error> {
+        view.put_u8(*self)
+    }
+}
+
+impl<V: Bytes + ?Sized> Take<V> for i8 {
+    fn take_from(view: &mut V) -> Result<i8, Error> {
+        view.take_i8()
+    }
+}
+
+impl<V: BytesMut + ?Sized> Put<V> for i8 {
+    fn put_into(&self, view: &mut V) -> Result<(), Error> {
+        view.put_i8(*self)
+    }
+}
+
+impl<V: Bytes + ?Sized, const N: usize> Take<V> for [u8; N] {
+    fn take_from(view: &mut V) -> Result<[u8; N], Error> {
+        view.take_array_copied()
+    }
+}
+
+impl<V: BytesMut + ?Sized, const N: usize> Put<V> for [u8; N] {
+    fn put_into(&self, view: &mut V) -> Result<(), Error> {
+        view.put_array(self)
+    }
+}
+
+macro_rules! impl_take_put_pod {
+    ($($typ:ident)*) => {
+        $(
+            impl<V: Bytes + ?Sized> Take<V> for crate::$typ {
+                fn take_from(view: &mut V) -> Result<crate::$typ, Error> {
+                    view.take_pod()
+                }
+            }
+
+            impl<V: BytesMut + ?Sized> Put<V> for crate::$typ {
+                fn put_into(&self, view: &mut V) -> Result<(), Error> {
+                    view.put_pod(self)
+                }
+            }
+        )*
+    }
+}
+
+impl_take_put_pod!(
+    U16Le U16Be I16Le I16Be U32Le U32Be I32Le I32Be U64Le U64Be I64Le I64Be
+    U128Le U128Be I128Le I128Be F32Le F32Be F64Le F64Be
+);
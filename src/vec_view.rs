@@ -0,0 +1,198 @@
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::{BytesMut,Error,Patch,Pod,Pos,ViewMut,pod};
+
+/// A view that grows a `Vec` as it's written to
+///
+/// `put` appends and is never out of bounds, so code written for [`BytesMut`](./trait.BytesMut.html)
+/// can serialize into fixed buffers and vectors alike. The position is the length of the vector.
+#[derive(Debug,Clone,Default,PartialEq,Eq)]
+pub struct VecView<T> {
+    vec: Vec<T>,
+}
+
+impl<T> VecView<T> {
+    pub fn new() -> VecView<T> {
+        VecView { vec: Vec::new() }
+    }
+
+    /// Start with room for `n` elements
+    pub fn with_capacity(n: usize) -> VecView<T> {
+        VecView { vec: Vec::with_capacity(n) }
+    }
+
+    /// Append to an existing vector
+    pub fn from_vec(vec: Vec<T>) -> VecView<T> {
+        VecView { vec }
+    }
+
+    /// What was written so far
+    pub fn as_slice(&self) -> &[T] {
+        &self.vec
+    }
+
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.vec
+    }
+}
+
+impl<T> From<Vec<T>> for VecView<T> {
+    fn from(vec: Vec<T>) -> VecView<T> {
+        VecView { vec }
+    }
+}
+
+/// The space after what was written, which is always empty
+impl<T> AsMut<[T]> for VecView<T> {
+    fn as_mut(&mut self) -> &mut [T] {
+        let len = self.vec.len();
+        &mut self.vec[len..]
+    }
+}
+
+impl<T> Pos for VecView<T> {
+    fn pos(&self) -> usize {
+        self.vec.len()
+    }
+}
+
+impl<T: Copy + Default> ViewMut<T> for VecView<T> {
+    fn put(&mut self, val: &[T]) -> Result<(), Error> {
+        self.vec.extend_from_slice(val);
+        Ok(())
+    }
+
+    /// Grow by `n` default elements to write in place
+    fn take_slice_mut(&mut self, n: usize) -> Result<&mut [T], Error> {
+        let pos = self.vec.len();
+        let end = pos.checked_add(n).ok_or_else(|| Error::overflow(pos))?;
+        self.vec.resize(end, T::default());
+        Ok(&mut self.vec[pos..])
+    }
+
+    /// As many as a vector can hold
+    fn room(&mut self) -> usize {
+        isize::MAX as usize - self.vec.len()
+    }
+
+    /// Allocate room for `n` more elements. It never fails.
+    fn reserve(&mut self, n: usize) -> Result<(), Error> {
+        self.vec.reserve(n);
+        Ok(())
+    }
+}
+
+impl BytesMut for VecView<u8> {
+    /// Checks the alignment of the spare capacity before growing, so a misaligned `T` leaves the
+    /// vector as it was
+    fn take_mut<T: Pod>(&mut self) -> Result<&mut T, Error> {
+        let pos = self.vec.len();
+        let size = mem::size_of::<T>();
+        self.vec.reserve(size);
+        pod::check_align_at::<T>(self.vec.as_ptr().wrapping_add(pos), size, pos)?;
+        let out = self.take_slice_mut(size)?;
+        Ok(unsafe { &mut *(out.as_mut_ptr() as *mut T) })
+    }
+}
+
+/// Fields are marked by their position
+impl Patch for VecView<u8> {
+    type Mark = usize;
+
+    fn reserve_bytes(&mut self, n: usize) -> Result<usize, Error> {
+        let pos = self.vec.len();
+        self.take_slice_mut(n)?;
+        Ok(pos)
+    }
+
+    fn fill_bytes(&mut self, pos: usize, val: &[u8]) -> Result<(), Error> {
+        let len = self.vec.len();
+        if pos > len { return Err(Error::out_of_bounds(pos, val.len(), 0)) }
+        Error::check(pos, val.len(), len - pos)?;
+        self.vec[pos .. pos + val.len()].copy_from_slice(val);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::op_ref, clippy::byte_char_slices)]
+mod tests {
+    use super::*;
+    use crate::{Be,ErrorKind};
+
+    #[test]
+    fn test_put() {
+        let mut a = VecView::new();
+
+        assert!(a.put(&[1,2]).is_ok());
+        assert!(a.put_u32_be(0x0304_0506).is_ok());
+        assert!(a.put_prefixed::<u8, Be>(b"ab").is_ok());
+        assert!(a.put_str_nul("c").is_ok());
+        assert!(a.pos() == 11);
+        assert!(a.as_slice() == &[1,2,3,4,5,6,2,b'a',b'b',b'c',0]);
+
+        let mut b = VecView::from_vec(a.into_vec());
+        assert!(b.put_fixed_str("x", 3, b' ').is_ok());
+        assert!(b.into_vec().ends_with(b"c\0x  "));
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mut a = VecView::<u8>::with_capacity(2);
+        assert!(a.capacity() >= 2);
+        assert!(a.reserve(100).is_ok());
+        assert!(a.capacity() >= 100);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn test_take_mut() {
+        let mut a = VecView::new();
+        assert!(a.put_u8(1).is_ok());
+
+        let slot = a.take_slice_mut(2).unwrap();
+        slot.copy_from_slice(&[2,3]);
+        assert!(a.take_mut::<[u8; 2]>().map(|x| *x = [4,5]).is_ok());
+        assert!(a.as_slice() == [1,2,3,4,5]);
+
+        let mut b = VecView::with_capacity(16);
+        assert!(b.put_u8(1).is_ok());
+        let next = b.as_slice().as_ptr().wrapping_add(1) as usize;
+        let align = mem::align_of::<u32>();
+        match b.take_mut::<u32>() {
+            Err(e) => {
+                assert!(next & (align - 1) != 0);
+                assert!(e.kind == ErrorKind::Misaligned { align });
+                assert!(b.len() == 1, "didn't grow");
+            }
+            Ok(_) => {
+                assert!(next & (align - 1) == 0);
+                assert!(b.len() == 5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_patch() {
+        let mut a = VecView::new();
+
+        let out = a.put_length_prefixed::<u32, Be, _>(|w| w.put_str_nul("hello"));
+        assert!(out.is_ok());
+        assert!(a.as_slice() == b"\0\0\0\x06hello\0");
+        assert!(a.fill_bytes(20, &[]) == Err(Error::out_of_bounds(20, 0, 0)), "past the end");
+        assert!(a.fill_bytes(9, &[1,2]) == Err(Error::out_of_bounds(9, 2, 1)));
+    }
+}
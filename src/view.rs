@@ -1,16 +1,58 @@
+use core::convert::TryFrom;
 use core::slice;
 use core::mem;
+use core::ptr;
 
-use crate::BadPos;
+use crate::{Error,ErrorKind,Limit,Pos,Pod,FromBytes,Endian,Le,Be,Ne,Prefix,leb128,pod};
 
-pub trait View<T>: AsRef<[T]> {
-    fn take(&mut self, n: usize) -> Result<&[T], BadPos>;
+pub trait View<T>: AsRef<[T]> + Pos {
+    fn take(&mut self, n: usize) -> Result<&[T], Error>;
+
+    /// Elements left to take. It's more than `as_ref` has when they aren't all in one slice, e.g.
+    /// in [`Segments`](./struct.Segments.html).
+    fn available(&self) -> usize {
+        self.as_ref().len()
+    }
+
+    /// Like [`take`](#tymethod.take) without moving the view.
+    fn peek(&self, n: usize) -> Result<&[T], Error> {
+        let buf = self.as_ref();
+        Error::check(self.pos(), n, buf.len())?;
+        Ok(&buf[..n])
+    }
+
+    /// Take `N` elements as an array, e.g. a hash or a magic number.
+    fn take_array<const N: usize>(&mut self) -> Result<&[T; N], Error> {
+        let out = self.take(N)?;
+        Ok(<&[T; N]>::try_from(out).unwrap())
+    }
+
+    /// Like [`take_array`](#method.take_array) but copies the array out of the view.
+    fn take_array_copied<const N: usize>(&mut self) -> Result<[T; N], Error>
+    where
+        T: Copy
+    {
+        Ok(*self.take_array::<N>()?)
+    }
+
+    /// Like [`take_array`](#method.take_array) without moving the view.
+    fn peek_array<const N: usize>(&self) -> Result<&[T; N], Error> {
+        let out = self.peek(N)?;
+        Ok(<&[T; N]>::try_from(out).unwrap())
+    }
+
+    /// A view of the next `n` elements that can't read past them, e.g. for a chunk body. Dropping
+    /// it takes what's left of them. See [`Limit`](./struct.Limit.html).
+    fn take_view(&mut self, n: usize) -> Result<Limit<'_, Self>, Error> {
+        Error::check(self.pos(), n, self.available())?;
+        Ok(Limit::skipping(self, n))
+    }
 }
 
 impl<T> View<T> for &[T] {
-    fn take(&mut self, n: usize) -> Result<&[T], BadPos> {
+    fn take(&mut self, n: usize) -> Result<&[T], Error> {
         let len = self.len();
-        if n > len { return Err(BadPos) }
+        Error::check(0, n, len)?;
         let out = unsafe { slice::from_raw_parts(self.as_ptr(), n) };
         *self = unsafe { slice::from_raw_parts(self.as_ptr().add(n), len - n) };
         Ok(out)
@@ -18,177 +60,484 @@ impl<T> View<T> for &[T] {
 }
 
 impl<T> View<T> for &mut [T] {
-    fn take(&mut self, n: usize) -> Result<&[T], BadPos> {
+    fn take(&mut self, n: usize) -> Result<&[T], Error> {
         let len = self.len();
-        if n > len { return Err(BadPos) }
+        Error::check(0, n, len)?;
         let out = unsafe { slice::from_raw_parts(self.as_ptr(), n) };
         *self = unsafe { slice::from_raw_parts_mut(self.as_mut_ptr().add(n), len - n) };
         Ok(out)
     }
 }
 
-macro_rules! take_int {
-    ($self:ident, $typ:tt::$conv:tt) => {
-        {
-            const SIZE: usize = mem::size_of::<$typ>();
-            let bytes = $self.take(SIZE)?;
-            Ok($typ::$conv(unsafe { *(bytes.as_ptr() as *const [u8; SIZE]) }))
-        }
+/// A view whose taken slices borrow from the underlying buffer instead of the view
+///
+/// With [`View::take`](./trait.View.html#tymethod.take) the result is tied to `&mut self`, so it
+/// must be dropped before taking again. Here the result lives as long as the buffer, so several
+/// fields can be borrowed at once.
+pub trait ViewRef<'a, T: 'a>: View<T> {
+    fn take_borrowed(&mut self, n: usize) -> Result<&'a [T], Error>;
+}
+
+impl<'a, T> ViewRef<'a, T> for &'a [T] {
+    fn take_borrowed(&mut self, n: usize) -> Result<&'a [T], Error> {
+        Error::check(0, n, self.len())?;
+        let (out, rest) = self.split_at(n);
+        *self = rest;
+        Ok(out)
+    }
+}
+
+impl<'a, T> ViewRef<'a, T> for &'a mut [T] {
+    fn take_borrowed(&mut self, n: usize) -> Result<&'a [T], Error> {
+        Error::check(0, n, self.len())?;
+        let (out, rest) = mem::take(self).split_at_mut(n);
+        *self = rest;
+        Ok(out)
     }
 }
 
 pub trait Bytes: View<u8> {
-    fn take_u8(&mut self) -> Result<u8, BadPos> {
+    /// Take any number in the byte order `E`, e.g. `take_num::<u32, Le>()`
+    fn take_num<N: FromBytes, E: Endian>(&mut self) -> Result<N, Error> {
+        let mut bytes = N::Bytes::default();
+        let n = bytes.as_ref().len();
+        bytes.as_mut().copy_from_slice(self.take(n)?);
+        Ok(E::from_bytes(bytes))
+    }
+
+    /// Like [`take_num`](#method.take_num) without moving the view.
+    fn peek_num<N: FromBytes, E: Endian>(&self) -> Result<N, Error> {
+        let mut bytes = N::Bytes::default();
+        let n = bytes.as_ref().len();
+        bytes.as_mut().copy_from_slice(self.peek(n)?);
+        Ok(E::from_bytes(bytes))
+    }
+
+    fn take_u8(&mut self) -> Result<u8, Error> {
         Ok(self.take(1)?[0])
     }
 
-    fn take_i8(&mut self) -> Result<i8, BadPos> {
+    fn take_i8(&mut self) -> Result<i8, Error> {
         Ok(self.take_u8()? as i8)
     }
 
-    fn take_u16_le(&mut self) -> Result<u16, BadPos> {
-        take_int!(self, u16::from_le_bytes)
+    fn take_u16_le(&mut self) -> Result<u16, Error> {
+        self.take_num::<u16, Le>()
+    }
+
+    fn take_u16_be(&mut self) -> Result<u16, Error> {
+        self.take_num::<u16, Be>()
+    }
+
+    fn take_u16_ne(&mut self) -> Result<u16, Error> {
+        self.take_num::<u16, Ne>()
+    }
+
+    fn take_i16_le(&mut self) -> Result<i16, Error> {
+        self.take_num::<i16, Le>()
+    }
+
+    fn take_i16_be(&mut self) -> Result<i16, Error> {
+        self.take_num::<i16, Be>()
+    }
+
+    fn take_i16_ne(&mut self) -> Result<i16, Error> {
+        self.take_num::<i16, Ne>()
+    }
+
+    fn take_u32_le(&mut self) -> Result<u32, Error> {
+        self.take_num::<u32, Le>()
+    }
+
+    fn take_u32_be(&mut self) -> Result<u32, Error> {
+        self.take_num::<u32, Be>()
+    }
+
+    fn take_u32_ne(&mut self) -> Result<u32, Error> {
+        self.take_num::<u32, Ne>()
+    }
+
+    fn take_i32_le(&mut self) -> Result<i32, Error> {
+        self.take_num::<i32, Le>()
+    }
+
+    fn take_i32_be(&mut self) -> Result<i32, Error> {
+        self.take_num::<i32, Be>()
+    }
+
+    fn take_i32_ne(&mut self) -> Result<i32, Error> {
+        self.take_num::<i32, Ne>()
     }
 
-    fn take_u16_be(&mut self) -> Result<u16, BadPos> {
-        take_int!(self, u16::from_be_bytes)
+    fn take_u64_le(&mut self) -> Result<u64, Error> {
+        self.take_num::<u64, Le>()
     }
 
-    fn take_u16_ne(&mut self) -> Result<u16, BadPos> {
-        take_int!(self, u16::from_ne_bytes)
+    fn take_u64_be(&mut self) -> Result<u64, Error> {
+        self.take_num::<u64, Be>()
     }
 
-    fn take_i16_le(&mut self) -> Result<i16, BadPos> {
-        take_int!(self, i16::from_le_bytes)
+    fn take_u64_ne(&mut self) -> Result<u64, Error> {
+        self.take_num::<u64, Ne>()
     }
 
-    fn take_i16_be(&mut self) -> Result<i16, BadPos> {
-        take_int!(self, i16::from_be_bytes)
+    fn take_i64_le(&mut self) -> Result<i64, Error> {
+        self.take_num::<i64, Le>()
     }
 
-    fn take_i16_ne(&mut self) -> Result<i16, BadPos> {
-        take_int!(self, i16::from_ne_bytes)
+    fn take_i64_be(&mut self) -> Result<i64, Error> {
+        self.take_num::<i64, Be>()
     }
 
-    fn take_u32_le(&mut self) -> Result<u32, BadPos> {
-        take_int!(self, u32::from_le_bytes)
+    fn take_i64_ne(&mut self) -> Result<i64, Error> {
+        self.take_num::<i64, Ne>()
     }
 
-    fn take_u32_be(&mut self) -> Result<u32, BadPos> {
-        take_int!(self, u32::from_be_bytes)
+    fn take_u128_le(&mut self) -> Result<u128, Error> {
+        self.take_num::<u128, Le>()
     }
 
-    fn take_u32_ne(&mut self) -> Result<u32, BadPos> {
-        take_int!(self, u32::from_ne_bytes)
+    fn take_u128_be(&mut self) -> Result<u128, Error> {
+        self.take_num::<u128, Be>()
     }
 
-    fn take_i32_le(&mut self) -> Result<i32, BadPos> {
-        take_int!(self, i32::from_le_bytes)
+    fn take_u128_ne(&mut self) -> Result<u128, Error> {
+        self.take_num::<u128, Ne>()
     }
 
-    fn take_i32_be(&mut self) -> Result<i32, BadPos> {
-        take_int!(self, i32::from_be_bytes)
+    fn take_i128_le(&mut self) -> Result<i128, Error> {
+        self.take_num::<i128, Le>()
     }
 
-    fn take_i32_ne(&mut self) -> Result<i32, BadPos> {
-        take_int!(self, i32::from_ne_bytes)
+    fn take_i128_be(&mut self) -> Result<i128, Error> {
+        self.take_num::<i128, Be>()
     }
 
-    fn take_u64_le(&mut self) -> Result<u64, BadPos> {
-        take_int!(self, u64::from_le_bytes)
+    fn take_i128_ne(&mut self) -> Result<i128, Error> {
+        self.take_num::<i128, Ne>()
     }
 
-    fn take_u64_be(&mut self) -> Result<u64, BadPos> {
-        take_int!(self, u64::from_be_bytes)
+    fn take_f32_le(&mut self) -> Result<f32, Error> {
+        self.take_num::<f32, Le>()
     }
 
-    fn take_u64_ne(&mut self) -> Result<u64, BadPos> {
-        take_int!(self, u64::from_ne_bytes)
+    fn take_f32_be(&mut self) -> Result<f32, Error> {
+        self.take_num::<f32, Be>()
     }
 
-    fn take_i64_le(&mut self) -> Result<i64, BadPos> {
-        take_int!(self, i64::from_le_bytes)
+    fn take_f32_ne(&mut self) -> Result<f32, Error> {
+        self.take_num::<f32, Ne>()
     }
 
-    fn take_i64_be(&mut self) -> Result<i64, BadPos> {
-        take_int!(self, i64::from_be_bytes)
+    fn take_f64_le(&mut self) -> Result<f64, Error> {
+        self.take_num::<f64, Le>()
     }
 
-    fn take_i64_ne(&mut self) -> Result<i64, BadPos> {
-        take_int!(self, i64::from_ne_bytes)
+    fn take_f64_be(&mut self) -> Result<f64, Error> {
+        self.take_num::<f64, Be>()
     }
 
-    fn take_u128_le(&mut self) -> Result<u128, BadPos> {
-        take_int!(self, u128::from_le_bytes)
+    fn take_f64_ne(&mut self) -> Result<f64, Error> {
+        self.take_num::<f64, Ne>()
     }
 
-    fn take_u128_be(&mut self) -> Result<u128, BadPos> {
-        take_int!(self, u128::from_be_bytes)
+    /// Cast the next bytes to a `T`, e.g. a `#[repr(C)]` header. The position must be aligned for
+    /// `T` or it's [`Misaligned`](./enum.ErrorKind.html#variant.Misaligned).
+    fn take_ref<T: Pod>(&mut self) -> Result<&T, Error> {
+        Ok(&self.take_slice_of::<T>(1)?[0])
     }
 
-    fn take_u128_ne(&mut self) -> Result<u128, BadPos> {
-        take_int!(self, u128::from_ne_bytes)
+    /// Copy the next bytes out as a `T`. Unlike [`take_ref`](#method.take_ref) they don't need
+    /// to be aligned.
+    fn take_pod<T: Pod>(&mut self) -> Result<T, Error> {
+        let out = self.take(mem::size_of::<T>())?;
+        Ok(unsafe { ptr::read_unaligned(out.as_ptr() as *const T) })
     }
 
-    fn take_i128_le(&mut self) -> Result<i128, BadPos> {
-        take_int!(self, i128::from_le_bytes)
+    /// Cast the next bytes to `count` `T`s, checked like [`take_ref`](#method.take_ref).
+    ///
+    /// The alignment is checked on what `peek` shows, before taking. Views whose `take` can
+    /// return other memory, like [`Segments`](./struct.Segments.html) and
+    /// [`Ring`](./struct.Ring.html), override this.
+    fn take_slice_of<T: Pod>(&mut self, count: usize) -> Result<&[T], Error> {
+        let pos = self.pos();
+        let size = pod::size_of_slice::<T>(count, pos)?;
+        pod::check_align::<T>(self.peek(size)?, pos)?;
+        let out = self.take(size)?;
+        Ok(unsafe { slice::from_raw_parts(out.as_ptr() as *const T, count) })
     }
 
-    fn take_i128_be(&mut self) -> Result<i128, BadPos> {
-        take_int!(self, i128::from_be_bytes)
+    /// Take an unsigned LEB128 number. Encodings longer than 10 bytes are
+    /// [`Overlong`](./enum.ErrorKind.html#variant.Overlong).
+    fn take_uleb128_u64(&mut self) -> Result<u64, Error> {
+        let (val, len) = leb128::decode_unsigned(self.as_ref(), 64, self.pos())?;
+        self.take(len)?;
+        Ok(val)
     }
 
-    fn take_i128_ne(&mut self) -> Result<i128, BadPos> {
-        take_int!(self, i128::from_ne_bytes)
+    /// Take an unsigned LEB128 number. Encodings longer than 5 bytes are
+    /// [`Overlong`](./enum.ErrorKind.html#variant.Overlong).
+    fn take_uleb128_u32(&mut self) -> Result<u32, Error> {
+        let (val, len) = leb128::decode_unsigned(self.as_ref(), 32, self.pos())?;
+        self.take(len)?;
+        Ok(val as u32)
     }
 
-    fn take_f32_le(&mut self) -> Result<f32, BadPos> {
-        take_int!(self, f32::from_le_bytes)
+    /// Take a signed LEB128 number. Encodings longer than 10 bytes are
+    /// [`Overlong`](./enum.ErrorKind.html#variant.Overlong).
+    fn take_sleb128_i64(&mut self) -> Result<i64, Error> {
+        let (val, len) = leb128::decode_signed(self.as_ref(), 64, self.pos())?;
+        self.take(len)?;
+        Ok(val)
     }
 
-    fn take_f32_be(&mut self) -> Result<f32, BadPos> {
-        take_int!(self, f32::from_be_bytes)
+    /// Take a signed LEB128 number. Encodings longer than 5 bytes are
+    /// [`Overlong`](./enum.ErrorKind.html#variant.Overlong).
+    fn take_sleb128_i32(&mut self) -> Result<i32, Error> {
+        let (val, len) = leb128::decode_signed(self.as_ref(), 32, self.pos())?;
+        self.take(len)?;
+        Ok(val as i32)
     }
 
-    fn take_f32_ne(&mut self) -> Result<f32, BadPos> {
-        take_int!(self, f32::from_ne_bytes)
+    /// Take bytes after a length prefix of type `L`, e.g. `take_prefixed::<u16, Le>()`.
+    fn take_prefixed<L: Prefix, E: Endian>(&mut self) -> Result<&[u8], Error> {
+        self.take_prefixed_max::<L, E>(usize::MAX)
     }
 
-    fn take_f64_le(&mut self) -> Result<f64, BadPos> {
-        take_int!(self, f64::from_le_bytes)
+    /// Like [`take_prefixed`](#method.take_prefixed) but lengths over `max` are
+    /// [`TooLong`](./enum.ErrorKind.html#variant.TooLong). Nothing is taken on error.
+    fn take_prefixed_max<L: Prefix, E: Endian>(&mut self, max: usize) -> Result<&[u8], Error> {
+        let pos = self.pos();
+        let mut buf = self.as_ref();
+        let len = L::take_len::<_, E>(&mut buf).map_err(|e| e.offset(pos))?;
+        let prefix = self.as_ref().len() - buf.len();
+        if len > max {
+            return Err(Error::new(ErrorKind::TooLong { max }, pos + prefix, len, buf.len()))
+        }
+        Error::check(pos + prefix, len, buf.len())?;
+        Ok(&self.take(prefix + len)?[prefix..])
     }
 
-    fn take_f64_be(&mut self) -> Result<f64, BadPos> {
-        take_int!(self, f64::from_be_bytes)
+    fn take_prefixed_str<L: Prefix, E: Endian>(&mut self) -> Result<&str, Error> {
+        self.take_prefixed_str_max::<L, E>(usize::MAX)
     }
 
-    fn take_f64_ne(&mut self) -> Result<f64, BadPos> {
-        take_int!(self, f64::from_ne_bytes)
+    fn take_prefixed_str_max<L: Prefix, E: Endian>(&mut self, max: usize) -> Result<&str, Error> {
+        let pos = self.pos();
+        let out = self.take_prefixed_max::<L, E>(max)?;
+        Error::from_utf8(pos, out)
     }
 
     /// Invalid UTF8 is considered an invalid position and out of bounds.
-    fn take_as_str(&mut self, n: usize) -> Result<&str, BadPos> {
-        core::str::from_utf8(self.take(n)?).map_err(|_| BadPos)
+    fn take_as_str(&mut self, n: usize) -> Result<&str, Error> {
+        let pos = self.pos();
+        Error::from_utf8(pos, self.take(n)?)
+    }
+
+    /// Take a string field of `width` bytes. It ends at the first NUL and trailing spaces are
+    /// trimmed, so NUL or space padded fields both work.
+    fn take_fixed_str(&mut self, width: usize) -> Result<&str, Error> {
+        let pos = self.pos();
+        let out = self.take(width)?;
+        let len = out.iter().position(|x| *x == b'\0').unwrap_or(width);
+        let len = out[..len].iter().rposition(|x| *x != b' ').map_or(0, |i| i + 1);
+        Error::from_utf8(pos, &out[..len])
     }
 
-    fn take_until_nul(&mut self) -> Result<&[u8], BadPos> {
-        let len = self.as_ref().iter().take_while(|x| **x != b'\0').count();
+    fn take_until_nul(&mut self) -> Result<&[u8], Error> {
+        let len = nul_position(self)?;
         let out = self.take(len + 1)?;
         let out = &out[..len];
         Ok(out)
     }
 
-    fn take_as_str_until_nul(&mut self) -> Result<&str, BadPos> {
+    fn take_as_str_until_nul(&mut self) -> Result<&str, Error> {
+        let pos = self.pos();
         let out = self.take_until_nul()?;
-        let out = core::str::from_utf8(out).map_err(|_| BadPos)?;
+        let out = Error::from_utf8(pos, out)?;
         Ok(out)
     }
+
+    fn peek_u8(&self) -> Result<u8, Error> {
+        Ok(self.peek(1)?[0])
+    }
+
+    fn peek_i8(&self) -> Result<i8, Error> {
+        Ok(self.peek_u8()? as i8)
+    }
+
+    fn peek_u16_le(&self) -> Result<u16, Error> {
+        self.peek_num::<u16, Le>()
+    }
+
+    fn peek_u16_be(&self) -> Result<u16, Error> {
+        self.peek_num::<u16, Be>()
+    }
+
+    fn peek_u16_ne(&self) -> Result<u16, Error> {
+        self.peek_num::<u16, Ne>()
+    }
+
+    fn peek_i16_le(&self) -> Result<i16, Error> {
+        self.peek_num::<i16, Le>()
+    }
+
+    fn peek_i16_be(&self) -> Result<i16, Error> {
+        self.peek_num::<i16, Be>()
+    }
+
+    fn peek_i16_ne(&self) -> Result<i16, Error> {
+        self.peek_num::<i16, Ne>()
+    }
+
+    fn peek_u32_le(&self) -> Result<u32, Error> {
+        self.peek_num::<u32, Le>()
+    }
+
+    fn peek_u32_be(&self) -> Result<u32, Error> {
+        self.peek_num::<u32, Be>()
+    }
+
+    fn peek_u32_ne(&self) -> Result<u32, Error> {
+        self.peek_num::<u32, Ne>()
+    }
+
+    fn peek_i32_le(&self) -> Result<i32, Error> {
+        self.peek_num::<i32, Le>()
+    }
+
+    fn peek_i32_be(&self) -> Result<i32, Error> {
+        self.peek_num::<i32, Be>()
+    }
+
+    fn peek_i32_ne(&self) -> Result<i32, Error> {
+        self.peek_num::<i32, Ne>()
+    }
+
+    fn peek_u64_le(&self) -> Result<u64, Error> {
+        self.peek_num::<u64, Le>()
+    }
+
+    fn peek_u64_be(&self) -> Result<u64, Error> {
+        self.peek_num::<u64, Be>()
+    }
+
+    fn peek_u64_ne(&self) -> Result<u64, Error> {
+        self.peek_num::<u64, Ne>()
+    }
+
+    fn peek_i64_le(&self) -> Result<i64, Error> {
+        self.peek_num::<i64, Le>()
+    }
+
+    fn peek_i64_be(&self) -> Result<i64, Error> {
+        self.peek_num::<i64, Be>()
+    }
+
+    fn peek_i64_ne(&self) -> Result<i64, Error> {
+        self.peek_num::<i64, Ne>()
+    }
+
+    fn peek_u128_le(&self) -> Result<u128, Error> {
+        self.peek_num::<u128, Le>()
+    }
+
+    fn peek_u128_be(&self) -> Result<u128, Error> {
+        self.peek_num::<u128, Be>()
+    }
+
+    fn peek_u128_ne(&self) -> Result<u128, Error> {
+        self.peek_num::<u128, Ne>()
+    }
+
+    fn peek_i128_le(&self) -> Result<i128, Error> {
+        self.peek_num::<i128, Le>()
+    }
+
+    fn peek_i128_be(&self) -> Result<i128, Error> {
+        self.peek_num::<i128, Be>()
+    }
+
+    fn peek_i128_ne(&self) -> Result<i128, Error> {
+        self.peek_num::<i128, Ne>()
+    }
+
+    fn peek_f32_le(&self) -> Result<f32, Error> {
+        self.peek_num::<f32, Le>()
+    }
+
+    fn peek_f32_be(&self) -> Result<f32, Error> {
+        self.peek_num::<f32, Be>()
+    }
+
+    fn peek_f32_ne(&self) -> Result<f32, Error> {
+        self.peek_num::<f32, Ne>()
+    }
+
+    fn peek_f64_le(&self) -> Result<f64, Error> {
+        self.peek_num::<f64, Le>()
+    }
+
+    fn peek_f64_be(&self) -> Result<f64, Error> {
+        self.peek_num::<f64, Be>()
+    }
+
+    fn peek_f64_ne(&self) -> Result<f64, Error> {
+        self.peek_num::<f64, Ne>()
+    }
+
+    fn peek_as_str(&self, n: usize) -> Result<&str, Error> {
+        Error::from_utf8(self.pos(), self.peek(n)?)
+    }
+
+    fn peek_until_nul(&self) -> Result<&[u8], Error> {
+        let len = nul_position(self)?;
+        Ok(&self.peek(len + 1)?[..len])
+    }
+
+    fn peek_as_str_until_nul(&self) -> Result<&str, Error> {
+        Error::from_utf8(self.pos(), self.peek_until_nul()?)
+    }
+}
+
+/// Finds the NUL ending the string at the start of the view
+fn nul_position<B: Bytes + ?Sized>(view: &B) -> Result<usize, Error> {
+    let buf = view.as_ref();
+    match buf.iter().position(|x| *x == b'\0') {
+        Some(len) => Ok(len),
+        None => Err(Error::new(ErrorKind::MissingTerminator, view.pos(), buf.len() + 1, buf.len())),
+    }
 }
 
 impl Bytes for &[u8] {}
 impl Bytes for &mut [u8] {}
 
+/// Byte methods of [`Bytes`](./trait.Bytes.html) that borrow from the underlying buffer
+pub trait BytesRef<'a>: ViewRef<'a, u8> + Bytes {
+    /// Invalid UTF8 is considered an invalid position and out of bounds.
+    fn take_borrowed_as_str(&mut self, n: usize) -> Result<&'a str, Error> {
+        let pos = self.pos();
+        Error::from_utf8(pos, self.take_borrowed(n)?)
+    }
+
+    fn take_borrowed_until_nul(&mut self) -> Result<&'a [u8], Error> {
+        let len = nul_position(self)?;
+        let out = self.take_borrowed(len + 1)?;
+        Ok(&out[..len])
+    }
+
+    fn take_borrowed_as_str_until_nul(&mut self) -> Result<&'a str, Error> {
+        let pos = self.pos();
+        let out = self.take_borrowed_until_nul()?;
+        Error::from_utf8(pos, out)
+    }
+}
+
+impl<'a> BytesRef<'a> for &'a [u8] {}
+impl<'a> BytesRef<'a> for &'a mut [u8] {}
+
 #[cfg(test)]
+#[allow(clippy::op_ref, clippy::byte_char_slices)]
 mod tests {
     use super::*;
 
@@ -491,5 +840,176 @@ mod tests {
         let b = a.take_until_nul();
         assert!(b == Ok(&[b'H',b'i']));
         assert!(a == &[]);
+
+        let mut a = &[b'H',b'i'][..];
+        let b = a.take_until_nul();
+        assert!(b == Err(Error::new(ErrorKind::MissingTerminator, 0, 3, 2)));
+        assert!(a == &[b'H',b'i']);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_take_num() {
+        fn sum<N: FromBytes + core::ops::Add<Output = N>, E: Endian>(mut a: &[u8]) -> N {
+            let b = a.take_num::<N, E>().unwrap();
+            let c = a.take_num::<N, E>().unwrap();
+            b + c
+        }
+
+        let a = &[1u16.to_le_bytes(),2u16.to_le_bytes()].concat()[..];
+        assert!(sum::<u16, Le>(a) == 3);
+
+        let a = &[1.5f64.to_be_bytes(),2f64.to_be_bytes()].concat()[..];
+        assert!(sum::<f64, Be>(a) == 3.5);
+
+        let mut a = &[1,2,3][..];
+        assert!(a.peek_num::<u16, Be>() == Ok(0x0102));
+        assert!(a.take_num::<u32, Ne>().is_err());
+        assert!(a.take_num::<i8, Ne>() == Ok(1));
+        assert!(a == &[2,3]);
+    }
+
+    #[test]
+    fn test_take_leb128() {
+        let mut a = &[0xE5,0x8E,0x26,0x7E,0x80,0x80,0x80,0x80,0x10,0x80][..];
+
+        assert!(a.take_uleb128_u64() == Ok(624485));
+        assert!(a.take_sleb128_i32() == Ok(-2));
+        assert!(a.take_uleb128_u32().unwrap_err() == Error::new(ErrorKind::Overflow, 0, 5, 6));
+        assert!(a.take_uleb128_u64() == Ok(1 << 32));
+        assert!(a.take_sleb128_i64() == Err(Error::out_of_bounds(0, 2, 1)));
+        assert!(a == &[0x80], "took none");
+    }
+
+    #[test]
+    fn test_take_prefixed() {
+        let mut a = &[2,0,b'H',b'i',0,3,b'a',b'b',b'c',2,0xFF,0xFF,9,1][..];
+
+        assert!(a.take_prefixed::<u16, Le>() == Ok(&b"Hi"[..]));
+        assert!(a.take_prefixed_str::<u16, Be>() == Ok("abc"));
+        assert!(a.take_prefixed_str::<crate::Uleb128, Le>().unwrap_err().kind == ErrorKind::InvalidUtf8 { valid_up_to: 0 });
+
+        let mut a = &[9,1,2][..];
+        let too_long = Error::new(ErrorKind::TooLong { max: 4 }, 1, 9, 2);
+        assert!(a.take_prefixed_max::<u8, Le>(4) == Err(too_long));
+        assert!(a.take_prefixed::<u8, Le>() == Err(Error::out_of_bounds(1, 9, 2)));
+        assert!(a == &[9,1,2], "took none");
+        assert!(a.take_prefixed::<u32, Le>().is_err());
+    }
+
+    #[test]
+    fn test_take_fixed_str() {
+        let mut a = &b"Hi\0\0ab  \0 x"[..];
+
+        assert!(a.take_fixed_str(4) == Ok("Hi"));
+        assert!(a.take_fixed_str(3) == Ok("ab"));
+        assert!(a.take_fixed_str(2) == Ok(""));
+        assert!(a.take_fixed_str(3).is_err());
+        assert!(a.take_fixed_str(2) == Ok(" x"));
+    }
+
+    #[test]
+    fn test_take_array() {
+        let mut a = &[0x89,b'P',b'N',b'G',1,2,3][..];
+
+        assert!(a.peek_array::<4>() == Ok(b"\x89PNG"));
+        assert!(a.take_array::<4>() == Ok(b"\x89PNG"));
+        assert!(a.take_array::<4>() == Err(Error::out_of_bounds(0, 4, 3)));
+        let b: [u8; 2] = a.take_array_copied().unwrap();
+        assert!(b == [1,2]);
+        assert!(a.take_array::<0>() == Ok(&[]));
+        assert!(a == &[3]);
+    }
+
+    #[repr(C, align(8))]
+    struct Aligned([u8; 16]);
+
+    #[test]
+    fn test_take_ref() {
+        #[derive(Clone,Copy,PartialEq,Debug)]
+        #[repr(C)]
+        struct Header {
+            magic: [u8; 4],
+            len: u32,
+        }
+        unsafe impl Pod for Header {}
+
+        let mut src = Aligned([0; 16]);
+        src.0[..4].copy_from_slice(b"ABCD");
+        src.0[4..8].copy_from_slice(&7u32.to_ne_bytes());
+        src.0[8..10].copy_from_slice(&1u16.to_ne_bytes());
+        src.0[10..12].copy_from_slice(&2u16.to_ne_bytes());
+
+        let mut a = &src.0[..];
+        assert!(a.take_ref::<Header>() == Ok(&Header { magic: *b"ABCD", len: 7 }));
+        assert!(a.take_slice_of::<u16>(2) == Ok(&[1,2][..]));
+        assert!(a.take_slice_of::<u32>(2) == Err(Error::out_of_bounds(0, 8, 4)));
+        assert!(a.take_slice_of::<u32>(usize::MAX).unwrap_err().kind == ErrorKind::Overflow);
+        assert!(a.take_ref::<u32>() == Ok(&0));
+
+        let mut b = &src.0[1..];
+        assert!(b.take_ref::<u32>().unwrap_err().kind == ErrorKind::Misaligned { align: 4 });
+        assert!(b.len() == 15, "took none");
+    }
+
+    #[test]
+    fn test_peek() {
+        let a = &[2,0,b'H',b'i',b'\0'][..];
+
+        assert!(a.peek(2) == Ok(&[2,0]));
+        assert!(a.peek(6).is_err());
+        assert!(a.peek_u8() == Ok(2));
+        assert!(a.peek_u16_le() == Ok(2));
+        assert!(a.peek_u16_be() == Ok(512));
+        assert!(a.peek_u32_le() == Ok(u32::from_le_bytes([2,0,b'H',b'i'])));
+        assert!(a == &[2,0,b'H',b'i',b'\0'], "peeked");
+
+        let mut b = a;
+        b.take(2).unwrap();
+        assert!(b.peek_as_str(2) == Ok("Hi"));
+        assert!(b.peek_until_nul() == Ok(&[b'H',b'i']));
+        assert!(b.peek_as_str_until_nul() == Ok("Hi"));
+        assert!(b.take_as_str_until_nul() == Ok("Hi"));
+        assert!(b.peek_u8().is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut a = &[1,2,3][..];
+        assert!(a.take(4) == Err(Error::out_of_bounds(0, 4, 3)));
+
+        let mut a = &[b'H',0xFF,b'\0'][..];
+        let b = a.take_as_str(2);
+        assert!(b == Err(Error::new(ErrorKind::InvalidUtf8 { valid_up_to: 1 }, 0, 2, 2)));
+
+        let mut a = &[b'H',0xFF,b'\0'][..];
+        let b = a.take_as_str_until_nul();
+        assert!(b.unwrap_err().kind == ErrorKind::InvalidUtf8 { valid_up_to: 1 });
+    }
+
+    #[test]
+    fn test_take_borrowed() {
+        let mut a = &[2,0,b'H',b'i',b'\0',b'!'][..];
+
+        let len = a.take_borrowed(2);
+        let name = a.take_borrowed_as_str_until_nul();
+        let rest = a.take_borrowed(1);
+        assert!(len == Ok(&[2,0]));
+        assert!(name == Ok("Hi"));
+        assert!(rest == Ok(&[b'!']));
+        assert!(a == &[]);
+
+        assert!(a.take_borrowed(1).is_err());
+    }
+
+    #[test]
+    fn test_take_borrowed_mut() {
+        let src = &mut [1,2,3][..];
+        let mut a = &mut *src;
+
+        let b = a.take_borrowed(1);
+        let c = a.take_borrowed(2);
+        assert!(b == Ok(&[1]));
+        assert!(c == Ok(&[2,3]));
+        assert!(a.take_borrowed(1).is_err());
+    }
+}
@@ -1,159 +1,281 @@
+use core::fmt;
 use core::slice;
+use core::mem;
 
-use crate::BadPos;
+use crate::{Counter,Error,ErrorKind,FmtView,Pos,Pod,ToBytes,Endian,Le,Be,Ne,Prefix,leb128,pod};
 
-pub trait ViewMut<T: Copy>: AsMut<[T]> {
-    fn put(&mut self, val: &[T]) -> Result<(), BadPos>;
+pub trait ViewMut<T: Copy>: AsMut<[T]> + Pos {
+    fn put(&mut self, val: &[T]) -> Result<(), Error>;
+
+    /// Take `n` elements to write in place. Views that can only `put` don't need to implement it:
+    /// the default is out of bounds.
+    fn take_slice_mut(&mut self, n: usize) -> Result<&mut [T], Error> {
+        Err(Error::out_of_bounds(self.pos(), n, 0))
+    }
+
+    /// Elements that can still be put. It's more than `as_mut` has when the space isn't one slice,
+    /// e.g. in a [`Ring`](./struct.Ring.html), or grows, e.g. in a [`VecView`](./struct.VecView.html).
+    fn room(&mut self) -> usize {
+        self.as_mut().len()
+    }
+
+    /// Make sure `n` more elements can be put. This lets writes made of several puts be
+    /// all-or-nothing.
+    fn reserve(&mut self, n: usize) -> Result<(), Error> {
+        let len = self.as_mut().len();
+        Error::check(self.pos(), n, len)
+    }
+
+    fn put_array<const N: usize>(&mut self, val: &[T; N]) -> Result<(), Error> {
+        self.put(val)
+    }
 }
 
 impl<T: Copy> ViewMut<T> for &mut [T] {
-    fn put(&mut self, val: &[T]) -> Result<(), BadPos> {
+    fn put(&mut self, val: &[T]) -> Result<(), Error> {
         let n = val.len();
         let len = self.len();
-        if n > len { return Err(BadPos) }
+        Error::check(0, n, len)?;
         let write = unsafe { slice::from_raw_parts_mut(self.as_mut_ptr(), n) };
         write.copy_from_slice(val);
         *self = unsafe { slice::from_raw_parts_mut(self.as_mut_ptr().add(n), len - n) };
         Ok(())
     }
+
+    fn take_slice_mut(&mut self, n: usize) -> Result<&mut [T], Error> {
+        Error::check(0, n, self.len())?;
+        let (out, rest) = mem::take(self).split_at_mut(n);
+        *self = rest;
+        Ok(out)
+    }
 }
 
 pub trait BytesMut: ViewMut<u8> {
-    fn put_u8(&mut self, val: u8) -> Result<(), BadPos> {
+    /// Put any number in the byte order `E`, e.g. `put_num::<f64, Be>(x)`
+    fn put_num<N: ToBytes, E: Endian>(&mut self, val: N) -> Result<(), Error> {
+        self.put(E::to_bytes(val).as_ref())
+    }
+
+    /// Cast the next bytes to a `T` to write in place. The position must be aligned for `T` or
+    /// it's [`Misaligned`](./enum.ErrorKind.html#variant.Misaligned), and nothing is taken.
+    ///
+    /// The alignment is checked on `as_mut` after [`reserve`](./trait.ViewMut.html#method.reserve),
+    /// so the bytes must show up there. Views whose space doesn't, like
+    /// [`VecView`](./struct.VecView.html) and [`Ring`](./struct.Ring.html), override this to check
+    /// it before they take.
+    fn take_mut<T: Pod>(&mut self) -> Result<&mut T, Error> {
+        let pos = self.pos();
+        let size = mem::size_of::<T>();
+        self.reserve(size)?;
+        let next = self.as_mut();
+        Error::check(pos, size, next.len())?;
+        pod::check_align::<T>(&next[..size], pos)?;
+        let out = self.take_slice_mut(size)?;
+        Ok(unsafe { &mut *(out.as_mut_ptr() as *mut T) })
+    }
+
+    /// Put the bytes of a `T`, e.g. a `#[repr(C)]` header
+    fn put_pod<T: Pod>(&mut self, val: &T) -> Result<(), Error> {
+        let size = mem::size_of::<T>();
+        self.put(unsafe { slice::from_raw_parts(val as *const T as *const u8, size) })
+    }
+
+    fn put_u8(&mut self, val: u8) -> Result<(), Error> {
         self.put(&[val])
     }
 
-    fn put_i8(&mut self, val: i8) -> Result<(), BadPos> {
+    fn put_i8(&mut self, val: i8) -> Result<(), Error> {
         self.put(&[val as u8])
     }
 
-    fn put_u16_le(&mut self, val: u16) -> Result<(), BadPos> {
-        self.put(&u16::to_le_bytes(val))
+    fn put_u16_le(&mut self, val: u16) -> Result<(), Error> {
+        self.put_num::<u16, Le>(val)
+    }
+
+    fn put_u16_be(&mut self, val: u16) -> Result<(), Error> {
+        self.put_num::<u16, Be>(val)
     }
 
-    fn put_u16_be(&mut self, val: u16) -> Result<(), BadPos> {
-        self.put(&u16::to_be_bytes(val))
+    fn put_u16_ne(&mut self, val: u16) -> Result<(), Error> {
+        self.put_num::<u16, Ne>(val)
     }
 
-    fn put_u16_ne(&mut self, val: u16) -> Result<(), BadPos> {
-        self.put(&u16::to_ne_bytes(val))
+    fn put_i16_le(&mut self, val: i16) -> Result<(), Error> {
+        self.put_num::<i16, Le>(val)
     }
 
-    fn put_i16_le(&mut self, val: i16) -> Result<(), BadPos> {
-        self.put(&i16::to_le_bytes(val))
+    fn put_i16_be(&mut self, val: i16) -> Result<(), Error> {
+        self.put_num::<i16, Be>(val)
     }
 
-    fn put_i16_be(&mut self, val: i16) -> Result<(), BadPos> {
-        self.put(&i16::to_be_bytes(val))
+    fn put_i16_ne(&mut self, val: i16) -> Result<(), Error> {
+        self.put_num::<i16, Ne>(val)
     }
 
-    fn put_i16_ne(&mut self, val: i16) -> Result<(), BadPos> {
-        self.put(&i16::to_ne_bytes(val))
+    fn put_u32_le(&mut self, val: u32) -> Result<(), Error> {
+        self.put_num::<u32, Le>(val)
     }
 
-    fn put_u32_le(&mut self, val: u32) -> Result<(), BadPos> {
-        self.put(&u32::to_le_bytes(val))
+    fn put_u32_be(&mut self, val: u32) -> Result<(), Error> {
+        self.put_num::<u32, Be>(val)
     }
 
-    fn put_u32_be(&mut self, val: u32) -> Result<(), BadPos> {
-        self.put(&u32::to_be_bytes(val))
+    fn put_u32_ne(&mut self, val: u32) -> Result<(), Error> {
+        self.put_num::<u32, Ne>(val)
     }
 
-    fn put_u32_ne(&mut self, val: u32) -> Result<(), BadPos> {
-        self.put(&u32::to_ne_bytes(val))
+    fn put_i32_le(&mut self, val: i32) -> Result<(), Error> {
+        self.put_num::<i32, Le>(val)
     }
 
-    fn put_i32_le(&mut self, val: i32) -> Result<(), BadPos> {
-        self.put(&i32::to_le_bytes(val))
+    fn put_i32_be(&mut self, val: i32) -> Result<(), Error> {
+        self.put_num::<i32, Be>(val)
     }
 
-    fn put_i32_be(&mut self, val: i32) -> Result<(), BadPos> {
-        self.put(&i32::to_be_bytes(val))
+    fn put_i32_ne(&mut self, val: i32) -> Result<(), Error> {
+        self.put_num::<i32, Ne>(val)
     }
 
-    fn put_i32_ne(&mut self, val: i32) -> Result<(), BadPos> {
-        self.put(&i32::to_ne_bytes(val))
+    fn put_u64_le(&mut self, val: u64) -> Result<(), Error> {
+        self.put_num::<u64, Le>(val)
     }
 
-    fn put_u64_le(&mut self, val: u64) -> Result<(), BadPos> {
-        self.put(&u64::to_le_bytes(val))
+    fn put_u64_be(&mut self, val: u64) -> Result<(), Error> {
+        self.put_num::<u64, Be>(val)
     }
 
-    fn put_u64_be(&mut self, val: u64) -> Result<(), BadPos> {
-        self.put(&u64::to_be_bytes(val))
+    fn put_u64_ne(&mut self, val: u64) -> Result<(), Error> {
+        self.put_num::<u64, Ne>(val)
     }
 
-    fn put_u64_ne(&mut self, val: u64) -> Result<(), BadPos> {
-        self.put(&u64::to_ne_bytes(val))
+    fn put_i64_le(&mut self, val: i64) -> Result<(), Error> {
+        self.put_num::<i64, Le>(val)
     }
 
-    fn put_i64_le(&mut self, val: i64) -> Result<(), BadPos> {
-        self.put(&i64::to_le_bytes(val))
+    fn put_i64_be(&mut self, val: i64) -> Result<(), Error> {
+        self.put_num::<i64, Be>(val)
     }
 
-    fn put_i64_be(&mut self, val: i64) -> Result<(), BadPos> {
-        self.put(&i64::to_be_bytes(val))
+    fn put_i64_ne(&mut self, val: i64) -> Result<(), Error> {
+        self.put_num::<i64, Ne>(val)
     }
 
-    fn put_i64_ne(&mut self, val: i64) -> Result<(), BadPos> {
-        self.put(&i64::to_ne_bytes(val))
+    fn put_u128_le(&mut self, val: u128) -> Result<(), Error> {
+        self.put_num::<u128, Le>(val)
     }
 
-    fn put_u128_le(&mut self, val: u128) -> Result<(), BadPos> {
-        self.put(&u128::to_le_bytes(val))
+    fn put_u128_be(&mut self, val: u128) -> Result<(), Error> {
+        self.put_num::<u128, Be>(val)
     }
 
-    fn put_u128_be(&mut self, val: u128) -> Result<(), BadPos> {
-        self.put(&u128::to_be_bytes(val))
+    fn put_u128_ne(&mut self, val: u128) -> Result<(), Error> {
+        self.put_num::<u128, Ne>(val)
     }
 
-    fn put_u128_ne(&mut self, val: u128) -> Result<(), BadPos> {
-        self.put(&u128::to_ne_bytes(val))
+    fn put_i128_le(&mut self, val: i128) -> Result<(), Error> {
+        self.put_num::<i128, Le>(val)
     }
 
-    fn put_i128_le(&mut self, val: i128) -> Result<(), BadPos> {
-        self.put(&i128::to_le_bytes(val))
+    fn put_i128_be(&mut self, val: i128) -> Result<(), Error> {
+        self.put_num::<i128, Be>(val)
     }
 
-    fn put_i128_be(&mut self, val: i128) -> Result<(), BadPos> {
-        self.put(&i128::to_be_bytes(val))
+    fn put_i128_ne(&mut self, val: i128) -> Result<(), Error> {
+        self.put_num::<i128, Ne>(val)
     }
 
-    fn put_i128_ne(&mut self, val: i128) -> Result<(), BadPos> {
-        self.put(&i128::to_ne_bytes(val))
+    fn put_f32_le(&mut self, val: f32) -> Result<(), Error> {
+        self.put_num::<f32, Le>(val)
     }
 
-    fn put_f32_le(&mut self, val: f32) -> Result<(), BadPos> {
-        self.put(&f32::to_le_bytes(val))
+    fn put_f32_be(&mut self, val: f32) -> Result<(), Error> {
+        self.put_num::<f32, Be>(val)
     }
 
-    fn put_f32_be(&mut self, val: f32) -> Result<(), BadPos> {
-        self.put(&f32::to_be_bytes(val))
+    fn put_f32_ne(&mut self, val: f32) -> Result<(), Error> {
+        self.put_num::<f32, Ne>(val)
     }
 
-    fn put_f32_ne(&mut self, val: f32) -> Result<(), BadPos> {
-        self.put(&f32::to_ne_bytes(val))
+    fn put_f64_le(&mut self, val: f64) -> Result<(), Error> {
+        self.put_num::<f64, Le>(val)
     }
 
-    fn put_f64_le(&mut self, val: f64) -> Result<(), BadPos> {
-        self.put(&f64::to_le_bytes(val))
+    fn put_f64_be(&mut self, val: f64) -> Result<(), Error> {
+        self.put_num::<f64, Be>(val)
     }
 
-    fn put_f64_be(&mut self, val: f64) -> Result<(), BadPos> {
-        self.put(&f64::to_be_bytes(val))
+    fn put_f64_ne(&mut self, val: f64) -> Result<(), Error> {
+        self.put_num::<f64, Ne>(val)
     }
 
-    fn put_f64_ne(&mut self, val: f64) -> Result<(), BadPos> {
-        self.put(&f64::to_ne_bytes(val))
+    /// Put `val` after its length as a prefix of type `L`, e.g. `put_prefixed::<u16, Le>(b"Hi")`.
+    /// Nothing is put if both don't fit.
+    fn put_prefixed<L: Prefix, E: Endian>(&mut self, val: &[u8]) -> Result<(), Error> {
+        let mut buf = [0; 10];
+        let mut prefix = &mut buf[..];
+        L::put_len::<_, E>(&mut prefix, val.len()).map_err(|e| e.offset(self.pos()))?;
+        let n = 10 - prefix.len();
+        self.reserve(n + val.len())?;
+        self.put(&buf[..n])?;
+        self.put(val)
+    }
+
+    /// Put a string and a NUL after it, like a C string. Nothing is put if both don't fit.
+    fn put_str_nul(&mut self, val: &str) -> Result<(), Error> {
+        self.reserve(val.len() + 1)?;
+        self.put(val.as_bytes())?;
+        self.put(b"\0")
+    }
+
+    /// Put a string field of `width` bytes, filling the rest with `pad`, e.g. `b'\0'` or `b' '`.
+    /// Strings longer than `width` are [`TooLong`](./enum.ErrorKind.html#variant.TooLong).
+    fn put_fixed_str(&mut self, val: &str, width: usize, pad: u8) -> Result<(), Error> {
+        if val.len() > width {
+            return Err(Error::new(ErrorKind::TooLong { max: width }, self.pos(), val.len(), width))
+        }
+        self.reserve(width)?;
+        self.put(val.as_bytes())?;
+        let pads = [pad; 16];
+        let mut left = width - val.len();
+        while left > 0 {
+            let n = left.min(pads.len());
+            self.put(&pads[..n])?;
+            left -= n;
+        }
+        Ok(())
+    }
+
+    /// Put an unsigned LEB128 number. It takes [`uleb128_len`](./fn.uleb128_len.html) bytes.
+    fn put_uleb128(&mut self, val: u64) -> Result<(), Error> {
+        let mut buf = [0; 10];
+        let len = leb128::encode_unsigned(val, &mut buf);
+        self.put(&buf[..len])
+    }
+
+    /// Put a signed LEB128 number. It takes [`sleb128_len`](./fn.sleb128_len.html) bytes.
+    fn put_sleb128(&mut self, val: i64) -> Result<(), Error> {
+        let mut buf = [0; 10];
+        let len = leb128::encode_signed(val, &mut buf);
+        self.put(&buf[..len])
+    }
+
+    /// Put formatted text, e.g. `put_fmt(format_args!("temp={}", t))`. Returns how many bytes it
+    /// took. Nothing is put if it doesn't all fit. It's formatted twice, once to measure it.
+    fn put_fmt(&mut self, args: fmt::Arguments) -> Result<usize, Error> {
+        let n = Counter::measure(|counter| FmtView::new(counter).format(args).map(|_| ()))
+            .map_err(|e| e.offset(self.pos()))?;
+        self.reserve(n)?;
+        FmtView::new(self).format(args)
     }
 }
 
 impl BytesMut for &mut [u8] {}
 
 #[cfg(test)]
+#[allow(clippy::op_ref, clippy::byte_char_slices)]
 mod tests {
     use super::*;
-    use crate::{Look,BytesMut};
+    use crate::{Look,Bytes,BytesMut};
 
     #[test]
     fn test_put() {
@@ -189,6 +311,105 @@ mod tests {
         assert!(src == &[0xFF,0xFF,0xFF,0xFF,b'O',b'k',b'\0'][..]);
     }
 
+    #[test]
+    fn test_put_num() {
+        fn put_pair<N: ToBytes + Copy, E: Endian>(mut a: &mut [u8], val: N) -> Result<(), Error> {
+            a.put_num::<N, E>(val)?;
+            a.put_num::<N, E>(val)
+        }
+
+        let mut src = [0u8; 4];
+        assert!(put_pair::<u16, Be>(&mut src[..], 0x0102).is_ok());
+        assert!(src == [1,2,1,2]);
+
+        let mut src = [0u8; 8];
+        assert!(put_pair::<f32, Le>(&mut src[..], 1.5).is_ok());
+        assert!(src[..4] == 1.5f32.to_le_bytes());
+        assert!(put_pair::<u64, Ne>(&mut src[..], 1).is_err());
+    }
+
+    #[test]
+    fn test_put_leb128() {
+        let mut src = [0u8; 5];
+        let mut a = &mut src[..];
+
+        assert!(a.put_uleb128(624485).is_ok());
+        assert!(a.put_sleb128(-2).is_ok());
+        assert!(a.put_sleb128(-123456).is_err(), "put none");
+        assert!(a.put_uleb128(1).is_ok());
+        assert!(src == [0xE5,0x8E,0x26,0x7E,0x01]);
+        assert!(crate::uleb128_len(624485) == 3);
+    }
+
+    #[test]
+    fn test_put_prefixed() {
+        let mut src = [0u8; 7];
+        let mut a = &mut src[..];
+
+        assert!(a.put_prefixed::<u16, Be>(b"Hi").is_ok());
+        assert!(a.put_prefixed::<crate::Uleb128, Le>(b"abc").is_err(), "put none");
+        assert!(a.put_prefixed::<u8, Le>(b"ab").is_ok());
+        assert!(a.put_prefixed::<u8, Le>(&[0; 256]).unwrap_err().kind == crate::ErrorKind::Overflow);
+        assert!(src == [0,2,b'H',b'i',2,b'a',b'b']);
+    }
+
+    #[test]
+    fn test_put_str() {
+        let mut src = [0xFFu8; 30];
+        let mut a = &mut src[..];
+
+        assert!(a.put_str_nul("Hi").is_ok());
+        assert!(a.put_fixed_str("ab", 4, b' ').is_ok());
+        assert!(a.put_fixed_str("abc", 2, b'\0').unwrap_err().kind == ErrorKind::TooLong { max: 2 });
+        assert!(a.put_fixed_str("x", 20, b'\0').is_ok());
+        assert!(a.put_fixed_str("", 4, b'\0').is_err(), "put none");
+        assert!(a.put_str_nul("abc").is_err(), "put none");
+        assert!(a == &[0xFF,0xFF,0xFF]);
+
+        let mut b = &src[..];
+        assert!(b.take_as_str_until_nul() == Ok("Hi"));
+        assert!(b.take_fixed_str(4) == Ok("ab"));
+        assert!(b.take_fixed_str(20) == Ok("x"));
+    }
+
+    #[test]
+    fn test_put_array() {
+        let mut src = [0u8; 6];
+        let mut a = &mut src[..];
+
+        assert!(a.put_array(&[0xAA; 6]).is_ok());
+        assert!(a.put_array(&[0xBB; 1]).is_err());
+        assert!(src == [0xAA; 6]);
+    }
+
+    #[test]
+    fn test_take_mut() {
+        #[repr(C, align(4))]
+        struct Aligned([u8; 8]);
+        let mut src = Aligned([0; 8]);
+
+        let mut a = &mut src.0[..];
+        *a.take_mut::<u32>().unwrap() = 1;
+        assert!(a.take_mut::<u64>().is_err());
+        *a.take_mut::<[u16; 2]>().unwrap() = [2,3];
+
+        let mut b = &mut src.0[1..];
+        assert!(b.take_mut::<u16>().unwrap_err().kind == ErrorKind::Misaligned { align: 2 });
+        assert!(b.len() == 7, "took none");
+        assert!(b.take_mut::<[u16; 4]>().unwrap_err().kind == ErrorKind::OutOfBounds);
+
+        let mut c = Look::new(&mut src.0[..]);
+        assert!(c.take_slice_mut(2).is_ok());
+        assert!(c.take_mut::<u16>().is_ok());
+        assert!(c.pos() == 4);
+
+        let mut expected = [0; 8];
+        expected[..4].copy_from_slice(&1u32.to_ne_bytes());
+        expected[4..6].copy_from_slice(&2u16.to_ne_bytes());
+        expected[6..].copy_from_slice(&3u16.to_ne_bytes());
+        assert!(src.0 == expected);
+    }
+
     #[test]
     fn test_put_u8() {
         let src: &mut [u8] = &mut [0,1][..];
@@ -0,0 +1,120 @@
+use views::{Bytes,BytesMut,EncodedLen,ErrorKind,Look,Put,Take,U32Be};
+#[cfg(not(feature = "derive"))]
+use views_derive::{Put,Take};
+
+#[derive(Debug,PartialEq,Take,Put)]
+#[views(le)]
+struct Header {
+    magic: [u8; 4],
+    version: u16,
+    #[views(be)]
+    flags: u32,
+    big: U32Be,
+}
+
+#[derive(Debug,PartialEq,Take,Put)]
+struct Entry<'a> {
+    len: u8,
+    #[views(len = "len")]
+    name: &'a str,
+    #[views(until_nul)]
+    comment: &'a [u8],
+}
+
+#[derive(Debug,PartialEq,Take,Put)]
+#[views(be, tag_type = "u16")]
+enum Message {
+    #[views(tag = 1)]
+    Ping,
+    #[views(tag = 2)]
+    Move(i32, i32),
+    #[views(tag = 3)]
+    Resize { #[views(le)] width: u16, height: u16 },
+}
+
+/// Fields and generics named like the locals of the derived code
+#[derive(Debug,PartialEq,Take,Put)]
+#[views(le)]
+struct Hygiene<__V> {
+    __pos: u16,
+    __view: u8,
+    inner: __V,
+}
+
+#[derive(Debug,PartialEq,Take,Put)]
+#[views(le)]
+enum HygieneTag {
+    #[views(tag = 7)]
+    A { __pos: u8, __view: u16 },
+}
+
+#[test]
+fn test_struct() {
+    let header = Header { magic: *b"VIEW", version: 2, flags: 1, big: U32Be::new(5) };
+    assert!(header.encoded_len() == Ok(14));
+    let mut buf = [0u8; 14];
+    let mut a = &mut buf[..];
+    assert!(header.put_into(&mut a).is_ok());
+    assert!(a.is_empty());
+    assert!(buf == *b"VIEW\x02\x00\x00\x00\x00\x01\x00\x00\x00\x05");
+
+    let mut b = &buf[..];
+    assert!(Header::take_from(&mut b) == Ok(header));
+    assert!(Header::take_from(&mut &buf[..13]).unwrap_err().kind == ErrorKind::OutOfBounds);
+}
+
+#[test]
+fn test_borrowed() {
+    let buf = *b"\x03abcxy\0rest";
+    let entry = {
+        let mut a = Look::new(&buf[..]);
+        let entry = Entry::take_from(&mut a).unwrap();
+        assert!(a.take_as_str(4) == Ok("rest"));
+        entry
+    };
+    assert!(entry == Entry { len: 3, name: "abc", comment: b"xy" });
+
+    let mut out = [0u8; 7];
+    assert!(entry.put_into(&mut &mut out[..]).is_ok());
+    assert!(out[..] == buf[..7]);
+}
+
+#[test]
+fn test_enum() {
+    let mut buf = [0u8; 20];
+    let mut a = &mut buf[..];
+    assert!(Message::Ping.put_into(&mut a).is_ok());
+    assert!(Message::Move(-1, 2).put_into(&mut a).is_ok());
+    assert!(Message::Resize { width: 3, height: 4 }.put_into(&mut a).is_ok());
+    assert!(a.put_u16_be(9).is_ok());
+
+    let mut b = Look::new(&buf[..]);
+    assert!(Message::take_from(&mut b) == Ok(Message::Ping));
+    assert!(Message::take_from(&mut b) == Ok(Message::Move(-1, 2)));
+    assert!(Message::take_from(&mut b) == Ok(Message::Resize { width: 3, height: 4 }));
+    let e = Message::take_from(&mut b).unwrap_err();
+    assert!(e.kind == ErrorKind::UnknownTag);
+    assert!(e.pos == 18);
+}
+
+#[test]
+fn test_put_checks() {
+    let mut buf = [0u8; 8];
+    let mut a = Look::new(&mut buf[..]);
+
+    let e = Entry { len: 5, name: "ab", comment: b"" }.put_into(&mut a).unwrap_err();
+    assert!(e.kind == ErrorKind::LengthMismatch);
+    assert!((e.pos, e.requested, e.available) == (1, 2, 5));
+    let e = Entry { len: 1, name: "a", comment: b"x\0y" }.put_into(&mut a).unwrap_err();
+    assert!(e.kind == ErrorKind::InteriorNul);
+    assert!(e.pos == 3, "after len and name");
+}
+
+#[test]
+fn test_hygiene() {
+    let value = Hygiene { __pos: 1, __view: 2, inner: HygieneTag::A { __pos: 3, __view: 4 } };
+    let mut buf = [0u8; 7];
+    assert!(value.put_into(&mut &mut buf[..]).is_ok());
+    assert!(buf == [1,0,2,7,3,4,0]);
+    assert!(Hygiene::take_from(&mut &buf[..]) == Ok(value));
+}
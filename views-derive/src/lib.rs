@@ -0,0 +1,362 @@
+//! Derives for the `Take` and `Put` traits of [`views`](https://docs.rs/views)
+//!
+//! Use them through the `derive` feature of `views`. See its `Take` trait for the attributes.
+
+extern crate proc_macro;
+
+use proc_macro2::{Span,TokenStream};
+use quote::{format_ident,quote};
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Expr, Fields, Generics, Ident,
+    LitStr, Meta, Result, Type,
+};
+
+#[proc_macro_derive(Take, attributes(views))]
+pub fn derive_take(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    take(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+#[proc_macro_derive(Put, attributes(views))]
+pub fn derive_put(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    put(&input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Attributes on the struct or enum
+#[derive(Default)]
+struct Container {
+    endian: Option<Ident>,
+    tag_type: Option<Type>,
+}
+
+/// How a field is taken and put
+enum Kind {
+    /// With `take_num` in a byte order
+    Num(Ident),
+    /// A `&[u8]` or `&str` with the length of an earlier field
+    Len(Ident),
+    /// A `&[u8]` or `&str` ending with a NUL
+    UntilNul,
+    /// With the field's own `Take` and `Put`
+    Nested,
+}
+
+struct Field {
+    name: Ident,
+    ty: Type,
+    kind: Kind,
+    is_str: bool,
+}
+
+/// The `views` marker struct for a byte order attribute
+fn endian(path: &syn::Path) -> Option<Ident> {
+    let name = [("le", "Le"), ("be", "Be"), ("ne", "Ne")].iter()
+        .find(|(attr, _)| path.is_ident(attr))?.1;
+    Some(Ident::new(name, Span::call_site()))
+}
+
+/// A name for a local of the generated code, hygienic so fields with the same name don't clash
+fn hidden(name: &str) -> Ident {
+    Ident::new(name, Span::mixed_site())
+}
+
+/// The view's type parameter, named unlike any of the type's own generics. Type names aren't
+/// hygienic, so it can't use [`hidden`].
+fn view_param(generics: &Generics) -> Ident {
+    let mut name = String::from("__V");
+    while generics.params.iter().any(|param| match param {
+        syn::GenericParam::Type(param) => param.ident == name,
+        syn::GenericParam::Const(param) => param.ident == name,
+        syn::GenericParam::Lifetime(_) => false,
+    }) {
+        name.push('_');
+    }
+    Ident::new(&name, Span::call_site())
+}
+
+fn is_byte(ty: &Type) -> bool {
+    match ty {
+        Type::Path(path) => path.path.is_ident("u8") || path.path.is_ident("i8"),
+        _ => false,
+    }
+}
+
+fn parse_container(input: &DeriveInput) -> Result<Container> {
+    let mut container = Container::default();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("views")) {
+        attr.parse_nested_meta(|meta| {
+            if let Some(endian) = endian(&meta.path) {
+                container.endian = Some(endian);
+            } else if meta.path.is_ident("tag_type") {
+                let ty: LitStr = meta.value()?.parse()?;
+                container.tag_type = Some(ty.parse()?);
+            } else {
+                return Err(meta.error("expected `le`, `be`, `ne` or `tag_type`"))
+            }
+            Ok(())
+        })?;
+    }
+    Ok(container)
+}
+
+fn is_number(ty: &Type) -> bool {
+    const NUMBERS: &[&str] = &[
+        "u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64", "u128", "i128", "f32", "f64",
+    ];
+    match ty {
+        Type::Path(path) => NUMBERS.iter().any(|n| path.path.is_ident(n)),
+        _ => false,
+    }
+}
+
+/// Whether `ty` is a `&str`, or else a `&[u8]`, for fields that need one of them
+fn is_str(ty: &Type) -> Result<bool> {
+    if let Type::Reference(reference) = ty {
+        match &*reference.elem {
+            Type::Path(path) if path.path.is_ident("str") => return Ok(true),
+            Type::Slice(_) => return Ok(false),
+            _ => {}
+        }
+    }
+    Err(Error::new_spanned(ty, "expected `&[u8]` or `&str`"))
+}
+
+fn parse_fields(fields: &Fields, container: &Container) -> Result<Vec<Field>> {
+    let mut out = Vec::new();
+    for (i, field) in fields.iter().enumerate() {
+        let name = field.ident.clone().unwrap_or_else(|| hidden(&format!("__{}", i)));
+        let mut kind = None;
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("views")) {
+            attr.parse_nested_meta(|meta| {
+                if let Some(endian) = endian(&meta.path) {
+                    kind = Some(Kind::Num(endian));
+                } else if meta.path.is_ident("len") {
+                    let len: LitStr = meta.value()?.parse()?;
+                    kind = Some(Kind::Len(len.parse()?));
+                } else if meta.path.is_ident("until_nul") {
+                    kind = Some(Kind::UntilNul);
+                } else {
+                    return Err(meta.error("expected `le`, `be`, `ne`, `len` or `until_nul`"))
+                }
+                Ok(())
+            })?;
+        }
+        let kind = match kind {
+            Some(kind) => kind,
+            None if is_number(&field.ty) => match &container.endian {
+                Some(endian) => Kind::Num(endian.clone()),
+                None if is_byte(&field.ty) => Kind::Nested,
+                None => return Err(Error::new_spanned(&field.ty, "numbers need `#[views(le)]`, `#[views(be)]` or `#[views(ne)]`")),
+            },
+            None => Kind::Nested,
+        };
+        let is_str = match kind {
+            Kind::Len(_) | Kind::UntilNul => is_str(&field.ty)?,
+            _ => false,
+        };
+        out.push(Field { name, ty: field.ty.clone(), kind, is_str });
+    }
+    Ok(out)
+}
+
+/// The `Take` and `Put` bound on the view. Types with a lifetime borrow from a `BytesRef`.
+fn take_bound(generics: &Generics) -> TokenStream {
+    match generics.lifetimes().next() {
+        Some(lifetime) => {
+            let lifetime = &lifetime.lifetime;
+            quote!(::views::BytesRef<#lifetime>)
+        }
+        None => quote!(::views::Bytes),
+    }
+}
+
+fn take_fields(fields: &[Field], generics: &mut Generics, v: &Ident) -> TokenStream {
+    let view = hidden("__view");
+    let takes = fields.iter().map(|field| {
+        let Field { name, ty, kind, is_str } = field;
+        let take = match kind {
+            Kind::Num(endian) => quote!(#view.take_num::<#ty, ::views::#endian>()),
+            Kind::Len(len) if *is_str => quote!(#view.take_borrowed_as_str(#len as usize)),
+            Kind::Len(len) => quote!(#view.take_borrowed(#len as usize)),
+            Kind::UntilNul if *is_str => quote!(#view.take_borrowed_as_str_until_nul()),
+            Kind::UntilNul => quote!(#view.take_borrowed_until_nul()),
+            Kind::Nested => {
+                generics.make_where_clause().predicates.push(parse_quote!(#ty: ::views::Take<#v>));
+                quote!(<#ty as ::views::Take<#v>>::take_from(#view))
+            }
+        };
+        quote!(let #name = #take?;)
+    });
+    quote!(#(#takes)*)
+}
+
+fn construct(path: TokenStream, fields: &Fields, parsed: &[Field]) -> TokenStream {
+    let names = parsed.iter().map(|f| &f.name);
+    match fields {
+        Fields::Named(_) => quote!(#path { #(#names),* }),
+        Fields::Unnamed(_) => quote!(#path(#(#names),*)),
+        Fields::Unit => path,
+    }
+}
+
+fn tag(container: &Container, input: &DeriveInput) -> Result<(Type, Ident)> {
+    let ty = container.tag_type.clone().unwrap_or_else(|| parse_quote!(u8));
+    let endian = match &container.endian {
+        Some(endian) => endian.clone(),
+        None if is_byte(&ty) => format_ident!("Le"),
+        None => return Err(Error::new_spanned(&input.ident, "tags wider than a byte need `#[views(le)]`, `#[views(be)]` or `#[views(ne)]`")),
+    };
+    Ok((ty, endian))
+}
+
+fn variant_tag(variant: &syn::Variant) -> Result<Expr> {
+    let mut tag = None;
+    for attr in variant.attrs.iter().filter(|a| a.path().is_ident("views")) {
+        if let Meta::List(_) = attr.meta {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("tag") {
+                    tag = Some(meta.value()?.parse()?);
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `tag`"))
+                }
+            })?;
+        }
+    }
+    tag.ok_or_else(|| Error::new_spanned(&variant.ident, "variants need `#[views(tag = ...)]`"))
+}
+
+fn take(input: &DeriveInput) -> Result<TokenStream> {
+    let v = view_param(&input.generics);
+    let (view, pos) = (hidden("__view"), hidden("__pos"));
+    let container = parse_container(input)?;
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = parse_fields(&data.fields, &container)?;
+            let takes = take_fields(&fields, &mut generics, &v);
+            let out = construct(quote!(#name), &data.fields, &fields);
+            quote!(#takes Ok(#out))
+        }
+        Data::Enum(data) => {
+            let (tag_ty, tag_endian) = tag(&container, input)?;
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let tag = variant_tag(variant)?;
+                let fields = parse_fields(&variant.fields, &container)?;
+                let takes = take_fields(&fields, &mut generics, &v);
+                let ident = &variant.ident;
+                let out = construct(quote!(#name::#ident), &variant.fields, &fields);
+                arms.push(quote!(#tag => { #takes Ok(#out) }));
+            }
+            quote! {
+                let #pos = ::views::Pos::pos(#view);
+                match #view.take_num::<#tag_ty, ::views::#tag_endian>()? {
+                    #(#arms)*
+                    _ => Err(::views::Error::new(::views::ErrorKind::UnknownTag, #pos, 0, 0)),
+                }
+            }
+        }
+        Data::Union(_) => return Err(Error::new_spanned(input, "unions can't derive `Take`")),
+    };
+    let bound = take_bound(&input.generics);
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    generics.params.push(parse_quote!(#v: #bound + ?Sized));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::views::Take<#v> for #name #ty_generics #where_clause {
+            fn take_from(#view: &mut #v) -> ::core::result::Result<Self, ::views::Error> {
+                #body
+            }
+        }
+    })
+}
+
+fn put_fields(fields: &[Field], generics: &mut Generics, v: &Ident) -> TokenStream {
+    let (view, pos, mismatch) = (hidden("__view"), hidden("__pos"), hidden("__kind"));
+    let puts = fields.iter().map(|field| {
+        let Field { name, ty, kind, is_str } = field;
+        let bytes = if *is_str { quote!(#name.as_bytes()) } else { quote!(#name) };
+        match kind {
+            Kind::Num(endian) => quote!(#view.put_num::<#ty, ::views::#endian>(*#name)?;),
+            Kind::Len(len) => quote! {
+                if *#len as usize != #bytes.len() {
+                    let #pos = ::views::Pos::pos(#view);
+                    let #mismatch = ::views::ErrorKind::LengthMismatch;
+                    return Err(::views::Error::new(#mismatch, #pos, #bytes.len(), *#len as usize));
+                }
+                #view.put(#bytes)?;
+            },
+            Kind::UntilNul => quote! {
+                if #bytes.contains(&0) {
+                    let #pos = ::views::Pos::pos(#view);
+                    return Err(::views::Error::new(::views::ErrorKind::InteriorNul, #pos, 0, 0));
+                }
+                #view.reserve(#bytes.len() + 1)?;
+                #view.put(#bytes)?;
+                #view.put_u8(0)?;
+            },
+            Kind::Nested => {
+                generics.make_where_clause().predicates.push(parse_quote!(#ty: ::views::Put<#v>));
+                quote!(::views::Put::<#v>::put_into(#name, #view)?;)
+            }
+        }
+    });
+    quote!(#(#puts)*)
+}
+
+fn put(input: &DeriveInput) -> Result<TokenStream> {
+    let v = view_param(&input.generics);
+    let view = hidden("__view");
+    let container = parse_container(input)?;
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let fields = parse_fields(&data.fields, &container)?;
+            let puts = put_fields(&fields, &mut generics, &v);
+            let pattern = construct(quote!(#name), &data.fields, &fields);
+            quote! {
+                let #pattern = self;
+                #puts
+                Ok(())
+            }
+        }
+        Data::Enum(data) => {
+            let (tag_ty, tag_endian) = tag(&container, input)?;
+            let mut arms = Vec::new();
+            for variant in &data.variants {
+                let tag = variant_tag(variant)?;
+                let fields = parse_fields(&variant.fields, &container)?;
+                let puts = put_fields(&fields, &mut generics, &v);
+                let ident = &variant.ident;
+                let pattern = construct(quote!(#name::#ident), &variant.fields, &fields);
+                arms.push(quote! {
+                    #pattern => {
+                        #view.put_num::<#tag_ty, ::views::#tag_endian>(#tag)?;
+                        #puts
+                    }
+                });
+            }
+            quote! {
+                match self {
+                    #(#arms)*
+                }
+                Ok(())
+            }
+        }
+        Data::Union(_) => return Err(Error::new_spanned(input, "unions can't derive `Put`")),
+    };
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    generics.params.push(parse_quote!(#v: ::views::BytesMut + ?Sized));
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::views::Put<#v> for #name #ty_generics #where_clause {
+            fn put_into(&self, #view: &mut #v) -> ::core::result::Result<(), ::views::Error> {
+                #body
+            }
+        }
+    })
+}